@@ -82,15 +82,27 @@ impl RollingFileAppender {
         directory: impl AsRef<Path>,
         file_name_prefix: impl AsRef<Path>,
     ) -> RollingFileAppender {
-        RollingFileAppender {
-            inner: InnerAppender::new(
-                directory.as_ref(),
-                file_name_prefix.as_ref(),
-                rotation,
-                Utc::now(),
-            )
-            .expect("Failed to create appender"),
-        }
+        Self::try_new(rotation, directory, file_name_prefix).expect("Failed to create appender")
+    }
+
+    /// Creates a new `RollingFileAppender`, returning an error rather than
+    /// panicking if the appender could not be created (for example, because
+    /// `directory` does not exist or is not writable).
+    ///
+    /// See [`RollingFileAppender::new`] for details on the appender's
+    /// behavior.
+    pub fn try_new(
+        rotation: Rotation,
+        directory: impl AsRef<Path>,
+        file_name_prefix: impl AsRef<Path>,
+    ) -> io::Result<RollingFileAppender> {
+        let inner = InnerAppender::new(
+            directory.as_ref(),
+            file_name_prefix.as_ref(),
+            rotation,
+            Utc::now(),
+        )?;
+        Ok(RollingFileAppender { inner })
     }
 }
 
@@ -219,6 +219,20 @@ pub trait Visit {
 
     /// Visit a value implementing `fmt::Debug`.
     fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug);
+
+    /// Visits a [`StructuredValue`], a minimal built-in map/list value.
+    ///
+    /// This is a typed counterpart to [`record_debug`][Self::record_debug]
+    /// for visitors -- such as JSON or other structured serializers -- that
+    /// want to preserve a [`StructuredValue`]'s map/list shape instead of
+    /// flattening it to a single debug-formatted string. The default
+    /// implementation just forwards to `record_debug`, so visitors that
+    /// don't care about the distinction need not override this.
+    #[cfg(feature = "alloc")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+    fn record_structured(&mut self, field: &Field, value: &StructuredValue) {
+        self.record_debug(field, value)
+    }
 }
 
 /// A field value of an erased type.
@@ -262,6 +276,91 @@ where
     DebugValue(t)
 }
 
+/// A minimal, built-in structured value: a map or list over primitives (and
+/// other `StructuredValue`s, for nesting).
+///
+/// This exists for users who want to attach map- or list-shaped data to a
+/// span or event without taking on the `valuable` crate as a dependency.
+/// It's recorded like any other [`Value`][Value]: pass it directly as a
+/// field's value, and a [`Visit`] implementation that cares about its shape
+/// (for example, a JSON serializer) can recover it via
+/// [`record_structured`][Visit::record_structured]; visitors that don't
+/// override that method see it flattened through its `Debug` implementation
+/// instead, via the default [`Visit::record_debug`].
+#[cfg(feature = "alloc")]
+#[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+#[derive(Clone, PartialEq)]
+pub enum StructuredValue {
+    /// A 64-bit signed integer.
+    I64(i64),
+    /// A 64-bit unsigned integer.
+    U64(u64),
+    /// A 64-bit floating point number.
+    F64(f64),
+    /// A boolean.
+    Bool(bool),
+    /// A string.
+    Str(alloc::string::String),
+    /// An ordered list of values.
+    List(alloc::vec::Vec<StructuredValue>),
+    /// An ordered map from string keys to values.
+    Map(alloc::vec::Vec<(alloc::string::String, StructuredValue)>),
+}
+
+#[cfg(feature = "alloc")]
+impl fmt::Debug for StructuredValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StructuredValue::I64(v) => fmt::Debug::fmt(v, f),
+            StructuredValue::U64(v) => fmt::Debug::fmt(v, f),
+            StructuredValue::F64(v) => fmt::Debug::fmt(v, f),
+            StructuredValue::Bool(v) => fmt::Debug::fmt(v, f),
+            StructuredValue::Str(v) => fmt::Debug::fmt(v, f),
+            StructuredValue::List(items) => f.debug_list().entries(items).finish(),
+            StructuredValue::Map(entries) => {
+                let mut map = f.debug_map();
+                for (k, v) in entries {
+                    map.entry(&format_args!("{}", k), v);
+                }
+                map.finish()
+            }
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl crate::sealed::Sealed for StructuredValue {}
+
+#[cfg(feature = "alloc")]
+#[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+impl Value for StructuredValue {
+    fn record(&self, key: &Field, visitor: &mut dyn Visit) {
+        visitor.record_structured(key, self)
+    }
+}
+
+macro_rules! impl_from_for_structured_value {
+    ($( $ty:ty => $variant:ident ),+ $(,)?) => {
+        $(
+            #[cfg(feature = "alloc")]
+            impl From<$ty> for StructuredValue {
+                fn from(value: $ty) -> Self {
+                    StructuredValue::$variant(value.into())
+                }
+            }
+        )+
+    };
+}
+
+impl_from_for_structured_value! {
+    i64 => I64,
+    u64 => U64,
+    f64 => F64,
+    bool => Bool,
+    &str => Str,
+    alloc::string::String => Str,
+}
+
 // ===== impl Visit =====
 
 impl<'a, 'b> Visit for fmt::DebugStruct<'a, 'b> {
@@ -988,4 +1087,30 @@ mod test {
         });
         assert_eq!(result, String::from("123"));
     }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn structured_value_from_impls() {
+        assert_eq!(StructuredValue::from(1i64), StructuredValue::I64(1));
+        assert_eq!(StructuredValue::from(1u64), StructuredValue::U64(1));
+        assert_eq!(StructuredValue::from(1.5f64), StructuredValue::F64(1.5));
+        assert_eq!(StructuredValue::from(true), StructuredValue::Bool(true));
+        assert_eq!(
+            StructuredValue::from("hi"),
+            StructuredValue::Str(String::from("hi"))
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn structured_value_debug_flattens_like_a_collection() {
+        let map = StructuredValue::Map(alloc::vec![(
+            String::from("a"),
+            StructuredValue::I64(1)
+        )]);
+        assert_eq!(format!("{:?}", map), r#"{a: 1}"#);
+
+        let list = StructuredValue::List(alloc::vec![StructuredValue::I64(1), StructuredValue::I64(2)]);
+        assert_eq!(format!("{:?}", list), "[1, 2]");
+    }
 }
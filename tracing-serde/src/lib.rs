@@ -181,13 +181,55 @@ use serde::{
 
 use tracing_core::{
     event::Event,
-    field::{Field, FieldSet, Visit},
+    field::{Field, FieldSet, StructuredValue, Visit},
     metadata::{Level, Metadata},
     span::{Attributes, Id, Record},
 };
 
 pub mod fields;
 
+#[cfg(feature = "stream")]
+pub mod stream;
+
+/// Serializes a [`StructuredValue`] natively as a JSON-style map or list,
+/// rather than flattening it through its `Debug` implementation.
+///
+/// This is a local newtype rather than a direct `impl Serialize for
+/// StructuredValue` because `tracing-serde` owns neither `Serialize` nor
+/// `StructuredValue`, and the orphan rules forbid implementing the former
+/// for the latter here.
+struct SerializableValue<'a>(&'a StructuredValue);
+
+impl<'a> Serialize for SerializableValue<'a> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self.0 {
+            StructuredValue::I64(v) => serializer.serialize_i64(*v),
+            StructuredValue::U64(v) => serializer.serialize_u64(*v),
+            StructuredValue::F64(v) => serializer.serialize_f64(*v),
+            StructuredValue::Bool(v) => serializer.serialize_bool(*v),
+            StructuredValue::Str(v) => serializer.serialize_str(v),
+            StructuredValue::List(items) => {
+                use serde::ser::SerializeSeq;
+                let mut seq = serializer.serialize_seq(Some(items.len()))?;
+                for item in items {
+                    seq.serialize_element(&SerializableValue(item))?;
+                }
+                seq.end()
+            }
+            StructuredValue::Map(entries) => {
+                let mut map = serializer.serialize_map(Some(entries.len()))?;
+                for (key, value) in entries {
+                    map.serialize_entry(key, &SerializableValue(value))?;
+                }
+                map.end()
+            }
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct SerializeField(Field);
 
@@ -335,6 +377,64 @@ impl<'a> Serialize for SerializeRecord<'a> {
     }
 }
 
+/// A serializable snapshot of a span's identity, full ancestor chain, and
+/// accumulated busy/idle timing.
+///
+/// Unlike [`SerializeAttributes`], which only captures the immediate parent
+/// recorded when a span was created, `SerializeSpanData` is meant to be
+/// assembled by a [`Collect`] that tracks a span's lineage and timing over
+/// its lifetime --- such as a registry-backed subscriber --- rather than
+/// wrapping a single `tracing-core` type directly. There is no [`AsSerde`]
+/// impl for it for this reason; construct it with [`SerializeSpanData::new`]
+/// once the lineage and timing are known.
+///
+/// [`Collect`]: tracing_core::Collect
+#[derive(Debug)]
+pub struct SerializeSpanData<'a> {
+    id: SerializeId<'a>,
+    metadata: SerializeMetadata<'a>,
+    parents: Vec<SerializeId<'a>>,
+    busy_ns: u64,
+    idle_ns: u64,
+}
+
+impl<'a> SerializeSpanData<'a> {
+    /// Constructs a `SerializeSpanData` from a span's `id` and `metadata`,
+    /// its `parents` ordered from nearest to root, and the total number of
+    /// nanoseconds it has spent busy (entered) and idle (open but not
+    /// entered) so far.
+    pub fn new(
+        id: &'a Id,
+        metadata: &'a Metadata<'a>,
+        parents: Vec<&'a Id>,
+        busy_ns: u64,
+        idle_ns: u64,
+    ) -> Self {
+        Self {
+            id: SerializeId(id),
+            metadata: SerializeMetadata(metadata),
+            parents: parents.into_iter().map(SerializeId).collect(),
+            busy_ns,
+            idle_ns,
+        }
+    }
+}
+
+impl<'a> Serialize for SerializeSpanData<'a> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut state = serializer.serialize_struct("SpanData", 5)?;
+        state.serialize_field("id", &self.id)?;
+        state.serialize_field("metadata", &self.metadata)?;
+        state.serialize_field("parents", &self.parents)?;
+        state.serialize_field("busy_ns", &self.busy_ns)?;
+        state.serialize_field("idle_ns", &self.idle_ns)?;
+        state.end()
+    }
+}
+
 /// Implements `tracing_core::field::Visit` for some `serde::ser::SerializeMap`.
 #[derive(Debug)]
 pub struct SerdeMapVisitor<S: SerializeMap> {
@@ -391,6 +491,14 @@ where
         }
     }
 
+    fn record_structured(&mut self, field: &Field, value: &StructuredValue) {
+        if self.state.is_ok() {
+            self.state = self
+                .serializer
+                .serialize_entry(field.name(), &SerializableValue(value))
+        }
+    }
+
     fn record_u64(&mut self, field: &Field, value: u64) {
         if self.state.is_ok() {
             self.state = self.serializer.serialize_entry(field.name(), &value)
@@ -443,6 +551,14 @@ where
         }
     }
 
+    fn record_structured(&mut self, field: &Field, value: &StructuredValue) {
+        if self.state.is_ok() {
+            self.state = self
+                .serializer
+                .serialize_field(field.name(), &SerializableValue(value))
+        }
+    }
+
     fn record_u64(&mut self, field: &Field, value: u64) {
         if self.state.is_ok() {
             self.state = self.serializer.serialize_field(field.name(), &value)
@@ -0,0 +1,850 @@
+//! A versioned, zstd-compressed, length-delimited binary format for
+//! streaming spans and events between processes -- including processes
+//! running different versions of `tracing-serde`.
+//!
+//! Unlike the [`Serialize`]-based wrappers in the crate root, which borrow
+//! directly from a live [`Event`]/[`Attributes`]/[`Record`] and can only be
+//! written out, [`Record`] and its writer/reader are owned types meant to
+//! survive a trip over a pipe, socket, or file: [`StreamWriter`] encodes a
+//! sequence of [`Record`]s, and [`StreamReader`] decodes them back, possibly
+//! in a different process built from a different `tracing-serde` version.
+//!
+//! # Wire format
+//!
+//! A stream begins with a 6-byte header:
+//!
+//! ```text
+//! +----------------+----------------------+
+//! | magic (4 bytes) | format version (u16) |
+//! +----------------+----------------------+
+//! ```
+//!
+//! followed by zero or more frames:
+//!
+//! ```text
+//! +---------+------------------------+--------------------------+-----------------------+
+//! | kind: u8 | compressed len: u32 BE | uncompressed len: u32 BE | zstd-compressed record |
+//! +---------+------------------------+--------------------------+-----------------------+
+//! ```
+//!
+//! Each frame's record payload is compressed independently, so a reader
+//! never needs to buffer more than one frame to make progress.
+//!
+//! # Compatibility guarantees
+//!
+//! - The magic bytes (`b"TSE1"`) and the layout of the header and frame
+//!   preamble (kind, compressed len, uncompressed len) are permanent. They
+//!   will never change for [`FORMAT_VERSION`] `1`.
+//! - [`FORMAT_VERSION`] only changes if the header or frame preamble itself
+//!   becomes wire-incompatible. [`StreamReader::new`] rejects a version it
+//!   doesn't recognize outright, rather than guessing at a layout it can't
+//!   verify.
+//! - New [`RecordKind`]s may be added in later releases. Because every
+//!   frame is length-delimited, a reader that doesn't recognize a frame's
+//!   kind byte can still skip over it and continue: [`StreamReader`]
+//!   surfaces these as [`Record::Unknown`] rather than failing the whole
+//!   stream. This is what makes an *older* reader forward-compatible with a
+//!   *newer* writer.
+//! - Within a known [`RecordKind`], new fields may only be appended after
+//!   the ones that already exist. [`StreamReader`] does not require a
+//!   frame's payload to be fully consumed by decoding: trailing bytes left
+//!   over from fields an older reader doesn't know about are simply
+//!   discarded along with the rest of the frame. Existing fields may never
+//!   be removed, reordered, or reinterpreted -- doing so requires a new
+//!   [`RecordKind`] instead.
+use std::convert::TryFrom;
+use std::io::{self, Read, Write};
+
+/// The magic bytes every stream begins with, used to reject non-stream
+/// input before attempting to parse a header.
+pub const MAGIC: [u8; 4] = *b"TSE1";
+
+/// The current wire format version. See the [compatibility guarantees](self#compatibility-guarantees).
+pub const FORMAT_VERSION: u16 = 1;
+
+/// The zstd compression level [`StreamWriter`] uses for each frame.
+const COMPRESSION_LEVEL: i32 = 3;
+
+/// The default maximum compressed or decompressed length [`StreamReader`]
+/// accepts for a single frame, chosen to comfortably fit a single span or
+/// event's worth of fields while still bounding how much memory a hostile or
+/// corrupted stream can make a reader allocate. Override it with
+/// [`StreamReader::with_max_frame_len`].
+pub const DEFAULT_MAX_FRAME_LEN: u32 = 16 * 1024 * 1024;
+
+/// The kind of a single frame in the stream, corresponding to one variant
+/// of [`Record`].
+///
+/// New variants may be added in later versions; see the
+/// [compatibility guarantees](self#compatibility-guarantees).
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum RecordKind {
+    NewSpan,
+    Record,
+    Enter,
+    Exit,
+    Close,
+    Event,
+}
+
+impl RecordKind {
+    fn tag(self) -> u8 {
+        match self {
+            RecordKind::NewSpan => 0,
+            RecordKind::Record => 1,
+            RecordKind::Enter => 2,
+            RecordKind::Exit => 3,
+            RecordKind::Close => 4,
+            RecordKind::Event => 5,
+        }
+    }
+
+    fn from_tag(tag: u8) -> Option<Self> {
+        match tag {
+            0 => Some(RecordKind::NewSpan),
+            1 => Some(RecordKind::Record),
+            2 => Some(RecordKind::Enter),
+            3 => Some(RecordKind::Exit),
+            4 => Some(RecordKind::Close),
+            5 => Some(RecordKind::Event),
+            _ => None,
+        }
+    }
+}
+
+/// An owned, decoded snapshot of a span's identifying metadata, suitable
+/// for storage or transmission -- unlike [`crate::SerializeMetadata`],
+/// which borrows from a live [`tracing_core::Metadata`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecordMetadata {
+    pub name: String,
+    pub target: String,
+    pub level: Level,
+    pub file: Option<String>,
+    pub line: Option<u32>,
+}
+
+/// The severity levels a [`RecordMetadata`] can carry, mirroring
+/// [`tracing_core::Level`].
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Level {
+    Error,
+    Warn,
+    Info,
+    Debug,
+    Trace,
+}
+
+impl From<tracing_core::Level> for Level {
+    fn from(level: tracing_core::Level) -> Self {
+        if level == tracing_core::Level::ERROR {
+            Level::Error
+        } else if level == tracing_core::Level::WARN {
+            Level::Warn
+        } else if level == tracing_core::Level::INFO {
+            Level::Info
+        } else if level == tracing_core::Level::DEBUG {
+            Level::Debug
+        } else {
+            Level::Trace
+        }
+    }
+}
+
+impl Level {
+    fn tag(self) -> u8 {
+        match self {
+            Level::Error => 0,
+            Level::Warn => 1,
+            Level::Info => 2,
+            Level::Debug => 3,
+            Level::Trace => 4,
+        }
+    }
+
+    fn from_tag(tag: u8) -> io::Result<Self> {
+        match tag {
+            0 => Ok(Level::Error),
+            1 => Ok(Level::Warn),
+            2 => Ok(Level::Info),
+            3 => Ok(Level::Debug),
+            4 => Ok(Level::Trace),
+            _ => Err(invalid_data(format!("unknown level tag {}", tag))),
+        }
+    }
+}
+
+/// An owned field value, mirroring the scalar variants [`tracing_core::field::Visit`]
+/// can record.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FieldValue {
+    Bool(bool),
+    F64(f64),
+    I64(i64),
+    U64(u64),
+    Str(String),
+    /// The `{:?}` rendering of a value recorded via `record_debug` or
+    /// `record_structured`, for field types with no dedicated variant here.
+    Debug(String),
+}
+
+/// A single decoded record from a [`StreamReader`], corresponding to one
+/// span lifecycle transition or event.
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum Record {
+    NewSpan {
+        id: u64,
+        metadata: RecordMetadata,
+        fields: Vec<(String, FieldValue)>,
+    },
+    Record {
+        id: u64,
+        fields: Vec<(String, FieldValue)>,
+    },
+    Enter {
+        id: u64,
+    },
+    Exit {
+        id: u64,
+    },
+    Close {
+        id: u64,
+    },
+    Event {
+        metadata: RecordMetadata,
+        fields: Vec<(String, FieldValue)>,
+    },
+    /// A frame whose [`RecordKind`] this version of `tracing-serde` doesn't
+    /// recognize, preserved so callers can forward or log it rather than
+    /// losing it silently. See the [compatibility guarantees](self#compatibility-guarantees).
+    Unknown { kind: u8, payload: Vec<u8> },
+}
+
+impl Record {
+    fn kind(&self) -> Option<RecordKind> {
+        match self {
+            Record::NewSpan { .. } => Some(RecordKind::NewSpan),
+            Record::Record { .. } => Some(RecordKind::Record),
+            Record::Enter { .. } => Some(RecordKind::Enter),
+            Record::Exit { .. } => Some(RecordKind::Exit),
+            Record::Close { .. } => Some(RecordKind::Close),
+            Record::Event { .. } => Some(RecordKind::Event),
+            Record::Unknown { .. } => None,
+        }
+    }
+
+    fn encode_payload(&self, buf: &mut Vec<u8>) {
+        match self {
+            Record::NewSpan {
+                id,
+                metadata,
+                fields,
+            } => {
+                write_u64(buf, *id);
+                encode_metadata(buf, metadata);
+                encode_fields(buf, fields);
+            }
+            Record::Record { id, fields } => {
+                write_u64(buf, *id);
+                encode_fields(buf, fields);
+            }
+            Record::Enter { id } | Record::Exit { id } | Record::Close { id } => {
+                write_u64(buf, *id);
+            }
+            Record::Event { metadata, fields } => {
+                encode_metadata(buf, metadata);
+                encode_fields(buf, fields);
+            }
+            Record::Unknown { payload, .. } => buf.extend_from_slice(payload),
+        }
+    }
+}
+
+fn write_u8(buf: &mut Vec<u8>, value: u8) {
+    buf.push(value);
+}
+
+fn write_u32(buf: &mut Vec<u8>, value: u32) {
+    buf.extend_from_slice(&value.to_be_bytes());
+}
+
+fn write_u64(buf: &mut Vec<u8>, value: u64) {
+    buf.extend_from_slice(&value.to_be_bytes());
+}
+
+fn write_str(buf: &mut Vec<u8>, value: &str) {
+    write_u32(buf, value.len() as u32);
+    buf.extend_from_slice(value.as_bytes());
+}
+
+fn write_option_str(buf: &mut Vec<u8>, value: Option<&str>) {
+    match value {
+        Some(value) => {
+            write_u8(buf, 1);
+            write_str(buf, value);
+        }
+        None => write_u8(buf, 0),
+    }
+}
+
+fn write_option_u32(buf: &mut Vec<u8>, value: Option<u32>) {
+    match value {
+        Some(value) => {
+            write_u8(buf, 1);
+            write_u32(buf, value);
+        }
+        None => write_u8(buf, 0),
+    }
+}
+
+fn encode_metadata(buf: &mut Vec<u8>, metadata: &RecordMetadata) {
+    write_str(buf, &metadata.name);
+    write_str(buf, &metadata.target);
+    write_u8(buf, metadata.level.tag());
+    write_option_str(buf, metadata.file.as_deref());
+    write_option_u32(buf, metadata.line);
+}
+
+fn encode_fields(buf: &mut Vec<u8>, fields: &[(String, FieldValue)]) {
+    write_u32(buf, fields.len() as u32);
+    for (name, value) in fields {
+        write_str(buf, name);
+        match value {
+            FieldValue::Bool(v) => {
+                write_u8(buf, 0);
+                write_u8(buf, *v as u8);
+            }
+            FieldValue::F64(v) => {
+                write_u8(buf, 1);
+                buf.extend_from_slice(&v.to_be_bytes());
+            }
+            FieldValue::I64(v) => {
+                write_u8(buf, 2);
+                buf.extend_from_slice(&v.to_be_bytes());
+            }
+            FieldValue::U64(v) => {
+                write_u8(buf, 3);
+                buf.extend_from_slice(&v.to_be_bytes());
+            }
+            FieldValue::Str(v) => {
+                write_u8(buf, 4);
+                write_str(buf, v);
+            }
+            FieldValue::Debug(v) => {
+                write_u8(buf, 5);
+                write_str(buf, v);
+            }
+        }
+    }
+}
+
+struct Cursor<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Cursor<'a> {
+    fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes }
+    }
+
+    fn take(&mut self, len: usize) -> io::Result<&'a [u8]> {
+        if self.bytes.len() < len {
+            return Err(invalid_data("unexpected end of frame payload"));
+        }
+        let (head, rest) = self.bytes.split_at(len);
+        self.bytes = rest;
+        Ok(head)
+    }
+
+    fn read_u8(&mut self) -> io::Result<u8> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn read_u32(&mut self) -> io::Result<u32> {
+        let bytes = self.take(4)?;
+        Ok(u32::from_be_bytes(<[u8; 4]>::try_from(bytes).unwrap()))
+    }
+
+    fn read_u64(&mut self) -> io::Result<u64> {
+        let bytes = self.take(8)?;
+        Ok(u64::from_be_bytes(<[u8; 8]>::try_from(bytes).unwrap()))
+    }
+
+    fn read_f64(&mut self) -> io::Result<f64> {
+        let bytes = self.take(8)?;
+        Ok(f64::from_be_bytes(<[u8; 8]>::try_from(bytes).unwrap()))
+    }
+
+    fn read_i64(&mut self) -> io::Result<i64> {
+        let bytes = self.take(8)?;
+        Ok(i64::from_be_bytes(<[u8; 8]>::try_from(bytes).unwrap()))
+    }
+
+    fn read_str(&mut self) -> io::Result<String> {
+        let len = self.read_u32()? as usize;
+        let bytes = self.take(len)?;
+        String::from_utf8(bytes.to_vec()).map_err(|e| invalid_data(e.to_string()))
+    }
+
+    fn read_option_str(&mut self) -> io::Result<Option<String>> {
+        match self.read_u8()? {
+            0 => Ok(None),
+            _ => Ok(Some(self.read_str()?)),
+        }
+    }
+
+    fn read_option_u32(&mut self) -> io::Result<Option<u32>> {
+        match self.read_u8()? {
+            0 => Ok(None),
+            _ => Ok(Some(self.read_u32()?)),
+        }
+    }
+}
+
+fn decode_metadata(cur: &mut Cursor<'_>) -> io::Result<RecordMetadata> {
+    Ok(RecordMetadata {
+        name: cur.read_str()?,
+        target: cur.read_str()?,
+        level: Level::from_tag(cur.read_u8()?)?,
+        file: cur.read_option_str()?,
+        line: cur.read_option_u32()?,
+    })
+}
+
+fn decode_fields(cur: &mut Cursor<'_>) -> io::Result<Vec<(String, FieldValue)>> {
+    let len = cur.read_u32()? as usize;
+    let mut fields = Vec::with_capacity(len);
+    for _ in 0..len {
+        let name = cur.read_str()?;
+        let value = match cur.read_u8()? {
+            0 => FieldValue::Bool(cur.read_u8()? != 0),
+            1 => FieldValue::F64(cur.read_f64()?),
+            2 => FieldValue::I64(cur.read_i64()?),
+            3 => FieldValue::U64(cur.read_u64()?),
+            4 => FieldValue::Str(cur.read_str()?),
+            5 => FieldValue::Debug(cur.read_str()?),
+            tag => return Err(invalid_data(format!("unknown field value tag {}", tag))),
+        };
+        fields.push((name, value));
+    }
+    Ok(fields)
+}
+
+fn decode_payload(kind: RecordKind, payload: &[u8]) -> io::Result<Record> {
+    let mut cur = Cursor::new(payload);
+    Ok(match kind {
+        RecordKind::NewSpan => Record::NewSpan {
+            id: cur.read_u64()?,
+            metadata: decode_metadata(&mut cur)?,
+            fields: decode_fields(&mut cur)?,
+        },
+        RecordKind::Record => Record::Record {
+            id: cur.read_u64()?,
+            fields: decode_fields(&mut cur)?,
+        },
+        RecordKind::Enter => Record::Enter {
+            id: cur.read_u64()?,
+        },
+        RecordKind::Exit => Record::Exit {
+            id: cur.read_u64()?,
+        },
+        RecordKind::Close => Record::Close {
+            id: cur.read_u64()?,
+        },
+        RecordKind::Event => Record::Event {
+            metadata: decode_metadata(&mut cur)?,
+            fields: decode_fields(&mut cur)?,
+        },
+    })
+}
+
+fn invalid_data(message: impl Into<String>) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.into())
+}
+
+/// Writes [`Record`]s to an underlying [`Write`] as zstd-compressed,
+/// length-delimited frames.
+///
+/// See the [module-level documentation](self) for the wire format and
+/// compatibility guarantees.
+#[derive(Debug)]
+pub struct StreamWriter<W> {
+    writer: W,
+}
+
+impl<W: Write> StreamWriter<W> {
+    /// Writes the stream header to `writer` and returns a `StreamWriter`
+    /// ready to accept records.
+    pub fn new(mut writer: W) -> io::Result<Self> {
+        writer.write_all(&MAGIC)?;
+        writer.write_all(&FORMAT_VERSION.to_be_bytes())?;
+        Ok(Self { writer })
+    }
+
+    /// Compresses and writes a single record as one frame.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called with a [`Record::Unknown`] -- that variant only
+    /// exists to represent frames a reader couldn't decode, and can never
+    /// be meaningfully written back out.
+    pub fn write_record(&mut self, record: &Record) -> io::Result<()> {
+        let kind = record
+            .kind()
+            .expect("Record::Unknown cannot be written to a stream");
+
+        let mut uncompressed = Vec::new();
+        record.encode_payload(&mut uncompressed);
+
+        let compressed = zstd::stream::encode_all(&uncompressed[..], COMPRESSION_LEVEL)?;
+
+        self.writer.write_all(&[kind.tag()])?;
+        self.writer
+            .write_all(&(compressed.len() as u32).to_be_bytes())?;
+        self.writer
+            .write_all(&(uncompressed.len() as u32).to_be_bytes())?;
+        self.writer.write_all(&compressed)?;
+        Ok(())
+    }
+
+    /// Flushes the underlying writer.
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+
+    /// Consumes the `StreamWriter`, returning the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+/// Reads [`Record`]s previously written by a [`StreamWriter`], possibly
+/// from a different `tracing-serde` version.
+///
+/// See the [module-level documentation](self) for the wire format and
+/// compatibility guarantees.
+#[derive(Debug)]
+pub struct StreamReader<R> {
+    reader: R,
+    max_frame_len: u32,
+}
+
+impl<R: Read> StreamReader<R> {
+    /// Reads and validates the stream header from `reader`.
+    ///
+    /// Returns an error if the magic bytes are missing, or if the header
+    /// declares a [`FORMAT_VERSION`] this build doesn't know how to read.
+    ///
+    /// The returned reader rejects any frame whose compressed or
+    /// decompressed length exceeds [`DEFAULT_MAX_FRAME_LEN`]; call
+    /// [`with_max_frame_len`](StreamReader::with_max_frame_len) to change
+    /// that limit.
+    pub fn new(mut reader: R) -> io::Result<Self> {
+        let mut magic = [0u8; 4];
+        reader.read_exact(&mut magic)?;
+        if magic != MAGIC {
+            return Err(invalid_data("input is not a tracing-serde event stream"));
+        }
+
+        let mut version = [0u8; 2];
+        reader.read_exact(&mut version)?;
+        let version = u16::from_be_bytes(version);
+        if version != FORMAT_VERSION {
+            return Err(invalid_data(format!(
+                "unsupported tracing-serde stream format version {} (this build supports {})",
+                version, FORMAT_VERSION
+            )));
+        }
+
+        Ok(Self {
+            reader,
+            max_frame_len: DEFAULT_MAX_FRAME_LEN,
+        })
+    }
+
+    /// Sets the largest compressed or decompressed frame size this reader
+    /// will accept, in bytes. A frame declaring a larger size in its header
+    /// is rejected before any memory is allocated for it or any
+    /// decompression is attempted.
+    ///
+    /// This bounds how much memory a single [`read_record`](Self::read_record)
+    /// call can be made to allocate, which matters when the stream comes
+    /// from an untrusted or unreliable peer -- see the
+    /// [module-level documentation](self). Defaults to
+    /// [`DEFAULT_MAX_FRAME_LEN`].
+    pub fn with_max_frame_len(mut self, max_frame_len: u32) -> Self {
+        self.max_frame_len = max_frame_len;
+        self
+    }
+
+    /// Reads and decodes the next frame, or returns `Ok(None)` at a clean
+    /// end of stream.
+    ///
+    /// A frame whose kind byte isn't recognized by this build is returned
+    /// as [`Record::Unknown`] rather than causing an error, per the
+    /// [compatibility guarantees](self#compatibility-guarantees).
+    ///
+    /// Returns an error without allocating a buffer for the frame's payload
+    /// if its compressed or decompressed length, as declared in the frame
+    /// header, exceeds [`max_frame_len`](Self::with_max_frame_len). The
+    /// actual decompressed size is also bounded independently of that
+    /// declared length, so a frame that decompresses to more data than its
+    /// header claims is rejected too, rather than being decompressed in
+    /// full.
+    pub fn read_record(&mut self) -> io::Result<Option<Record>> {
+        let kind = match read_u8_or_eof(&mut self.reader)? {
+            Some(kind) => kind,
+            None => return Ok(None),
+        };
+
+        let mut compressed_len = [0u8; 4];
+        self.reader.read_exact(&mut compressed_len)?;
+        let compressed_len = u32::from_be_bytes(compressed_len);
+
+        let mut uncompressed_len = [0u8; 4];
+        self.reader.read_exact(&mut uncompressed_len)?;
+        let uncompressed_len = u32::from_be_bytes(uncompressed_len);
+
+        if compressed_len > self.max_frame_len || uncompressed_len > self.max_frame_len {
+            return Err(invalid_data(format!(
+                "frame length {} exceeds the configured maximum of {} bytes",
+                compressed_len.max(uncompressed_len),
+                self.max_frame_len,
+            )));
+        }
+
+        let mut compressed = vec![0u8; compressed_len as usize];
+        self.reader.read_exact(&mut compressed)?;
+
+        // Decompress through a reader capped at `max_frame_len + 1` bytes,
+        // rather than trusting `uncompressed_len` up front: a frame can
+        // truthfully declare a small `uncompressed_len` while its
+        // compressed bytes actually decode to far more (a zstd bomb), and
+        // this cap still catches that case even though the length check
+        // above didn't.
+        let mut decoder = zstd::stream::Decoder::new(&compressed[..])?;
+        let mut uncompressed = Vec::new();
+        (&mut decoder)
+            .take(u64::from(self.max_frame_len) + 1)
+            .read_to_end(&mut uncompressed)?;
+        if uncompressed.len() as u64 > u64::from(self.max_frame_len) {
+            return Err(invalid_data(format!(
+                "decompressed frame exceeds the configured maximum of {} bytes",
+                self.max_frame_len,
+            )));
+        }
+        if uncompressed.len() as u32 != uncompressed_len {
+            return Err(invalid_data(
+                "decompressed frame length did not match the header",
+            ));
+        }
+
+        Ok(Some(match RecordKind::from_tag(kind) {
+            Some(kind) => decode_payload(kind, &uncompressed)?,
+            None => Record::Unknown {
+                kind,
+                payload: uncompressed,
+            },
+        }))
+    }
+
+    /// Consumes the `StreamReader`, returning the underlying reader.
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+/// Like `Read::read_exact` for a single byte, but treats a zero-byte read
+/// (rather than a truncated one) as a clean end of stream.
+fn read_u8_or_eof<R: Read>(reader: &mut R) -> io::Result<Option<u8>> {
+    let mut byte = [0u8; 1];
+    loop {
+        return match reader.read(&mut byte) {
+            Ok(0) => Ok(None),
+            Ok(_) => Ok(Some(byte[0])),
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => Err(e),
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_records() -> Vec<Record> {
+        vec![
+            Record::NewSpan {
+                id: 1,
+                metadata: RecordMetadata {
+                    name: "request".to_string(),
+                    target: "my_app".to_string(),
+                    level: Level::Info,
+                    file: Some("src/lib.rs".to_string()),
+                    line: Some(42),
+                },
+                fields: vec![("attempt".to_string(), FieldValue::U64(1))],
+            },
+            Record::Record {
+                id: 1,
+                fields: vec![("attempt".to_string(), FieldValue::U64(2))],
+            },
+            Record::Enter { id: 1 },
+            Record::Event {
+                metadata: RecordMetadata {
+                    name: "event src/lib.rs:43".to_string(),
+                    target: "my_app".to_string(),
+                    level: Level::Warn,
+                    file: None,
+                    line: None,
+                },
+                fields: vec![(
+                    "message".to_string(),
+                    FieldValue::Debug("retrying".to_string()),
+                )],
+            },
+            Record::Exit { id: 1 },
+            Record::Close { id: 1 },
+        ]
+    }
+
+    #[test]
+    #[ignore = "run manually with -- --ignored to regenerate tests/golden/stream_v1.bin"]
+    fn generate_golden_stream_v1() {
+        let mut buf = Vec::new();
+        let mut writer = StreamWriter::new(&mut buf).unwrap();
+        for record in &sample_records() {
+            writer.write_record(record).unwrap();
+        }
+        writer.flush().unwrap();
+        std::fs::write(
+            concat!(env!("CARGO_MANIFEST_DIR"), "/tests/golden/stream_v1.bin"),
+            &buf,
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn roundtrips_every_record_kind() {
+        let records = sample_records();
+
+        let mut buf = Vec::new();
+        let mut writer = StreamWriter::new(&mut buf).unwrap();
+        for record in &records {
+            writer.write_record(record).unwrap();
+        }
+        writer.flush().unwrap();
+
+        let mut reader = StreamReader::new(&buf[..]).unwrap();
+        let mut decoded = Vec::new();
+        while let Some(record) = reader.read_record().unwrap() {
+            decoded.push(record);
+        }
+
+        assert_eq!(decoded, records);
+    }
+
+    #[test]
+    fn rejects_bad_magic() {
+        let err = StreamReader::new(&b"nope!!"[..]).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn rejects_unsupported_version() {
+        let mut buf = Vec::new();
+        buf.extend_from_slice(&MAGIC);
+        buf.extend_from_slice(&999u16.to_be_bytes());
+        let err = StreamReader::new(&buf[..]).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn rejects_declared_length_over_the_configured_maximum() {
+        let mut buf = Vec::new();
+        buf.extend_from_slice(&MAGIC);
+        buf.extend_from_slice(&FORMAT_VERSION.to_be_bytes());
+
+        let payload = vec![0u8; 64];
+        let compressed = zstd::stream::encode_all(&payload[..], COMPRESSION_LEVEL).unwrap();
+        buf.push(RecordKind::Event.tag());
+        buf.extend_from_slice(&(compressed.len() as u32).to_be_bytes());
+        // Lie about the decompressed length rather than the true 64 bytes.
+        buf.extend_from_slice(&(payload.len() as u32 * 1_000_000).to_be_bytes());
+        buf.extend_from_slice(&compressed);
+
+        let mut reader = StreamReader::new(&buf[..]).unwrap().with_max_frame_len(1024);
+        let err = reader.read_record().unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn rejects_a_frame_that_decompresses_past_its_declared_length() {
+        // A frame whose header understates how much it will actually
+        // decompress to must still be caught, not just one that honestly
+        // declares an oversized length.
+        let mut buf = Vec::new();
+        buf.extend_from_slice(&MAGIC);
+        buf.extend_from_slice(&FORMAT_VERSION.to_be_bytes());
+
+        let payload = vec![0u8; 64 * 1024];
+        let compressed = zstd::stream::encode_all(&payload[..], COMPRESSION_LEVEL).unwrap();
+        buf.push(RecordKind::Event.tag());
+        buf.extend_from_slice(&(compressed.len() as u32).to_be_bytes());
+        // Understate the decompressed length so the up-front header check
+        // alone wouldn't catch this.
+        buf.extend_from_slice(&1u32.to_be_bytes());
+        buf.extend_from_slice(&compressed);
+
+        let mut reader = StreamReader::new(&buf[..]).unwrap().with_max_frame_len(1024);
+        let err = reader.read_record().unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn unrecognized_kind_becomes_unknown_record() {
+        let mut buf = Vec::new();
+        buf.extend_from_slice(&MAGIC);
+        buf.extend_from_slice(&FORMAT_VERSION.to_be_bytes());
+
+        let payload = b"from the future".to_vec();
+        let compressed = zstd::stream::encode_all(&payload[..], COMPRESSION_LEVEL).unwrap();
+        buf.push(0xFF); // a kind tag no released version will ever assign
+        buf.extend_from_slice(&(compressed.len() as u32).to_be_bytes());
+        buf.extend_from_slice(&(payload.len() as u32).to_be_bytes());
+        buf.extend_from_slice(&compressed);
+
+        let mut reader = StreamReader::new(&buf[..]).unwrap();
+        let record = reader.read_record().unwrap().unwrap();
+        assert_eq!(
+            record,
+            Record::Unknown {
+                kind: 0xFF,
+                payload,
+            }
+        );
+    }
+
+    /// A golden encoding of `sample_records()` produced by this version of
+    /// the format, checked in so that a future change which accidentally
+    /// breaks decoding of already-written streams fails this test instead
+    /// of silently shipping.
+    ///
+    /// If you intentionally change how a known [`RecordKind`] is encoded,
+    /// per the [compatibility guarantees](super#compatibility-guarantees)
+    /// that's only allowed by introducing a new `RecordKind`, so this
+    /// golden file should never need to change for [`FORMAT_VERSION`] `1`.
+    const GOLDEN_STREAM_V1: &[u8] = include_bytes!("../tests/golden/stream_v1.bin");
+
+    #[test]
+    fn decodes_golden_stream_v1() {
+        let mut reader = StreamReader::new(GOLDEN_STREAM_V1).unwrap();
+        let mut decoded = Vec::new();
+        while let Some(record) = reader.read_record().unwrap() {
+            decoded.push(record);
+        }
+        assert_eq!(decoded, sample_records());
+    }
+}
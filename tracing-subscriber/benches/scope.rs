@@ -0,0 +1,46 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use tracing_subscriber::registry::LookupSpan;
+
+/// Depths of span trees to benchmark `SpanRef::scope` traversal over.
+const DEPTHS: &[usize] = &[1, 2, 4, 8, 16, 32];
+
+fn scope(c: &mut Criterion) {
+    let subscriber = tracing_subscriber::registry();
+    let _guard = tracing::collect::set_default(subscriber);
+
+    let mut group = c.benchmark_group("scope");
+    for &depth in DEPTHS {
+        let leaf = (0..depth).fold(None, |parent, i| {
+            let span = if let Some(parent) = parent {
+                tracing::info_span!(parent: &parent, "span", i)
+            } else {
+                tracing::info_span!("span", i)
+            };
+            Some(span)
+        });
+        let leaf = leaf.expect("depth is always > 0");
+
+        group.bench_with_input(BenchmarkId::new("leaf_to_root", depth), &leaf, |b, leaf| {
+            tracing::dispatch::get_default(|dispatch| {
+                let registry = dispatch
+                    .downcast_ref::<tracing_subscriber::Registry>()
+                    .expect("dispatch should be a Registry");
+                let span = registry.span(&leaf.id().unwrap()).unwrap();
+                b.iter(|| span.scope().count())
+            });
+        });
+
+        group.bench_with_input(BenchmarkId::new("from_root", depth), &leaf, |b, leaf| {
+            tracing::dispatch::get_default(|dispatch| {
+                let registry = dispatch
+                    .downcast_ref::<tracing_subscriber::Registry>()
+                    .expect("dispatch should be a Registry");
+                let span = registry.span(&leaf.id().unwrap()).unwrap();
+                b.iter(|| span.scope().from_root().count())
+            });
+        });
+    }
+}
+
+criterion_group!(benches, scope);
+criterion_main!(benches);
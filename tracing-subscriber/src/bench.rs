@@ -0,0 +1,192 @@
+//! A no-op-but-realistic subscriber for measuring the overhead a layer
+//! stack adds to spans and events.
+//!
+//! Answering "how much does our logging configuration cost?" usually means
+//! reaching for a full benchmarking harness and hand-rolling a baseline to
+//! subtract out. [`baseline`] is that baseline, written once: a [`Registry`]
+//! wrapped in a [`Counter`] subscriber pre-filtered at [`Level::INFO`],
+//! which visits every field and tracks span/event counts (so it's not
+//! optimized away) without doing any real work with them. [`overhead_per_event`]
+//! and [`overhead_per_span`] then time a collector -- typically `baseline()`
+//! with a user's own layers added on top -- against that floor.
+//!
+//! This gives a quick, in-process before/after comparison; it's not a
+//! substitute for a proper `criterion` benchmark when the numbers actually
+//! matter, but it's enough to catch a layer that's orders of magnitude more
+//! expensive than expected before it reaches production.
+//!
+//! This module requires the "bench" feature flag.
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+
+use tracing_core::{
+    dispatch::{self, Dispatch},
+    field::{Field, Visit},
+    span, Collect, Event,
+};
+
+use crate::filter::LevelFilter;
+use crate::registry::LookupSpan;
+use crate::subscribe::{CollectExt, Context, Subscribe};
+
+/// How many iterations to run, and discard, before timing begins.
+const WARMUP_ITERATIONS: usize = 1_000;
+
+/// The number of spans and events a [`Counter`] has seen.
+#[derive(Debug, Default)]
+pub struct Counts {
+    spans: AtomicUsize,
+    events: AtomicUsize,
+}
+
+impl Counts {
+    /// Returns the number of spans recorded so far.
+    pub fn spans(&self) -> usize {
+        self.spans.load(Ordering::Relaxed)
+    }
+
+    /// Returns the number of events recorded so far.
+    pub fn events(&self) -> usize {
+        self.events.load(Ordering::Relaxed)
+    }
+}
+
+struct DiscardFields;
+
+impl Visit for DiscardFields {
+    fn record_debug(&mut self, _field: &Field, _value: &dyn std::fmt::Debug) {}
+}
+
+/// A [`Subscribe`] that visits every field of every span and event it sees,
+/// then discards them, while counting how many of each it's seen.
+///
+/// This is meant to stand in for a "real" subscriber in [`baseline`]: field
+/// visitation is most of what a formatting or exporting layer spends its
+/// time on, so a subscriber that skips it entirely would understate the
+/// overhead a realistic layer stack adds.
+#[derive(Clone, Debug, Default)]
+pub struct Counter {
+    counts: Arc<Counts>,
+}
+
+impl Counter {
+    /// Returns a new `Counter` and a handle to the [`Counts`] it will
+    /// record into.
+    pub fn new() -> (Self, Arc<Counts>) {
+        let counts = Arc::new(Counts::default());
+        (
+            Self {
+                counts: counts.clone(),
+            },
+            counts,
+        )
+    }
+}
+
+impl<C> Subscribe<C> for Counter
+where
+    C: Collect + for<'a> LookupSpan<'a>,
+{
+    fn new_span(&self, attrs: &span::Attributes<'_>, _id: &span::Id, _ctx: Context<'_, C>) {
+        attrs.record(&mut DiscardFields);
+        self.counts.spans.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn on_record(&self, _id: &span::Id, values: &span::Record<'_>, _ctx: Context<'_, C>) {
+        values.record(&mut DiscardFields);
+    }
+
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, C>) {
+        event.record(&mut DiscardFields);
+        self.counts.events.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+/// Builds a [`Registry`] wrapped in a [`Counter`] filtered at
+/// [`Level::INFO`][tracing_core::Level::INFO], along with the [`Counts`] it
+/// records into.
+///
+/// This is meant to be used as a starting point for measuring a user's own
+/// layer stack's overhead: build the collector to be measured by adding the
+/// user's own subscribers on top of the returned one (e.g. with
+/// [`SubscribeExt::with`]), then pass it to [`overhead_per_event`] or
+/// [`overhead_per_span`], and compare against calling those functions on
+/// `baseline()` alone.
+///
+/// [`Registry`]: crate::registry::Registry
+pub fn baseline() -> (impl Collect + for<'a> LookupSpan<'a> + Send + Sync + 'static, Arc<Counts>) {
+    let (counter, counts) = Counter::new();
+    let subscriber = crate::registry().with(counter.with_filter(LevelFilter::INFO));
+    (subscriber, counts)
+}
+
+/// Measures the average time spent recording one event under `collector`.
+///
+/// Runs a fixed number of warmup iterations to let any lazy initialization
+/// (callsite registration, allocator warmup, and so on) happen before
+/// timing starts, then times `iterations` more and returns the average time
+/// per event.
+pub fn overhead_per_event(collector: impl Into<Dispatch>, iterations: usize) -> Duration {
+    let dispatch = collector.into();
+    dispatch::with_default(&dispatch, || {
+        for _ in 0..WARMUP_ITERATIONS {
+            tracing::info!("warmup");
+        }
+        let start = Instant::now();
+        for _ in 0..iterations {
+            tracing::info!("bench");
+        }
+        start.elapsed() / iterations as u32
+    })
+}
+
+/// Measures the average time spent entering and exiting one span under
+/// `collector`.
+///
+/// Runs a fixed number of warmup iterations before timing starts, for the
+/// same reasons as [`overhead_per_event`].
+pub fn overhead_per_span(collector: impl Into<Dispatch>, iterations: usize) -> Duration {
+    let dispatch = collector.into();
+    dispatch::with_default(&dispatch, || {
+        for _ in 0..WARMUP_ITERATIONS {
+            tracing::info_span!("warmup").in_scope(|| {});
+        }
+        let start = Instant::now();
+        for _ in 0..iterations {
+            tracing::info_span!("bench").in_scope(|| {});
+        }
+        start.elapsed() / iterations as u32
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counter_counts_spans_and_events() {
+        let (subscriber, counts) = baseline();
+        dispatch::with_default(&Dispatch::new(subscriber), || {
+            tracing::info_span!("span", answer = 42).in_scope(|| {
+                tracing::info!(status = 200, "event");
+            });
+        });
+
+        assert_eq!(counts.spans(), 1);
+        assert_eq!(counts.events(), 1);
+    }
+
+    #[test]
+    fn overhead_helpers_report_nonzero_durations() {
+        let (subscriber, _counts) = baseline();
+        assert!(overhead_per_event(subscriber, 100) > Duration::default());
+
+        let (subscriber, _counts) = baseline();
+        assert!(overhead_per_span(subscriber, 100) > Duration::default());
+    }
+}
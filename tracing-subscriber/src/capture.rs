@@ -0,0 +1,73 @@
+//! A control handle for capturing a temporarily wider window of
+//! diagnostics, on demand.
+//!
+//! Widening a filter, swapping in a dedicated writer, and remembering to
+//! put both back are three separate [`reload::Handle`] operations if done
+//! by hand --- and it's easy to leave one of them swapped if the other is
+//! forgotten. [`CaptureHandle`] packages the three into a single
+//! `capture_for` call.
+use std::{thread, time::Duration};
+
+use crate::filter::LevelFilter;
+use crate::fmt::writer::BoxMakeWriter;
+use crate::fmt::MakeWriter;
+use crate::reload;
+
+/// Temporarily widens an enabled [`LevelFilter`] and redirects output to a
+/// dedicated writer for a fixed duration, then restores both.
+///
+/// A `CaptureHandle` is built from the [`reload::Handle`]s for the level
+/// filter and writer it controls, which must already be wrapped in
+/// [`reload::Subscriber`]s when the subscriber stack is built.
+#[derive(Clone, Debug)]
+pub struct CaptureHandle {
+    level: reload::Handle<LevelFilter>,
+    writer: reload::Handle<BoxMakeWriter>,
+}
+
+impl CaptureHandle {
+    /// Returns a new `CaptureHandle` controlling `level` and `writer`.
+    pub fn new(level: reload::Handle<LevelFilter>, writer: reload::Handle<BoxMakeWriter>) -> Self {
+        Self { level, writer }
+    }
+
+    /// Widens the enabled level to `capture_level` and redirects output to
+    /// `capture_writer`, restoring the previous level and writer after
+    /// `duration` elapses.
+    ///
+    /// The restore happens on a background thread, so this does not block
+    /// the calling thread. If a previous capture is still in progress when
+    /// this is called, it is overwritten: the level and writer captured
+    /// here are what's restored once `duration` elapses, not whatever was
+    /// in place when the previous capture began.
+    pub fn capture_for<W>(
+        &self,
+        duration: Duration,
+        capture_level: LevelFilter,
+        capture_writer: W,
+    ) -> Result<(), reload::Error>
+    where
+        W: for<'writer> MakeWriter<'writer> + Send + Sync + 'static,
+    {
+        let mut previous_level = LevelFilter::OFF;
+        self.level.modify(|current| {
+            previous_level = *current;
+            *current = capture_level;
+        })?;
+
+        let mut previous_writer = BoxMakeWriter::new(std::io::sink);
+        self.writer.modify(|current| {
+            previous_writer = std::mem::replace(current, BoxMakeWriter::new(capture_writer));
+        })?;
+
+        let level = self.level.clone();
+        let writer = self.writer.clone();
+        thread::spawn(move || {
+            thread::sleep(duration);
+            let _ = level.modify(|current| *current = previous_level);
+            let _ = writer.modify(|current| *current = previous_writer);
+        });
+
+        Ok(())
+    }
+}
@@ -0,0 +1,256 @@
+//! Building a [`Collect`][tracing_core::Collect] from a deserialized
+//! configuration, so a deployment can choose its tracing setup from a config
+//! file instead of recompiling.
+//!
+//! [`Config`] is deliberately format-agnostic: it only depends on `serde`,
+//! not on any particular data format crate, so it can be deserialized from
+//! YAML, TOML, JSON, or anything else `serde` supports. It covers the [fmt]
+//! layer's most common options -- format, writer, and a per-layer
+//! [`Targets`] or [`EnvFilter`] directive -- rather than every [`Subscribe`]
+//! in this crate; a setup that needs something more specialized still needs
+//! to be composed in code.
+//!
+//! This module requires the "config" feature flag.
+//!
+//! [fmt]: crate::fmt
+//!
+//! # Examples
+//!
+//! ```
+//! use tracing_subscriber::config::Config;
+//!
+//! let config: Config = serde_json::from_str(
+//!     r#"{
+//!         "layers": [
+//!             { "format": "compact", "writer": "stderr", "filter": { "targets": "my_crate=warn" } }
+//!         ]
+//!     }"#,
+//! )
+//! .unwrap();
+//!
+//! let collector = config.build().unwrap();
+//! tracing::collect::set_global_default(collector).ok();
+//! ```
+use std::fmt;
+
+use serde::Deserialize;
+use tracing_core::Collect;
+
+use crate::filter::{EnvFilter, Filter, Targets};
+use crate::fmt::writer::BoxMakeWriter;
+use crate::registry::Registry;
+use crate::subscribe::{CollectExt, DynamicSubscriber, Subscribe};
+
+/// Where a [`LayerConfig`]'s output should be written.
+#[derive(Copy, Clone, Debug, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WriterTarget {
+    /// Write to standard output.
+    Stdout,
+    /// Write to standard error.
+    Stderr,
+}
+
+impl Default for WriterTarget {
+    fn default() -> Self {
+        WriterTarget::Stdout
+    }
+}
+
+impl WriterTarget {
+    fn into_make_writer(self) -> BoxMakeWriter {
+        match self {
+            WriterTarget::Stdout => BoxMakeWriter::new(std::io::stdout),
+            WriterTarget::Stderr => BoxMakeWriter::new(std::io::stderr),
+        }
+    }
+}
+
+/// How a [`LayerConfig`]'s events and spans should be formatted.
+#[derive(Copy, Clone, Debug, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FormatConfig {
+    /// The default, multi-line human-readable format.
+    Full,
+    /// A single-line human-readable format.
+    Compact,
+    /// Newline-delimited JSON.
+    #[cfg(feature = "json")]
+    Json,
+}
+
+impl Default for FormatConfig {
+    fn default() -> Self {
+        FormatConfig::Full
+    }
+}
+
+/// A filter directive for a [`LayerConfig`].
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FilterConfig {
+    /// A [`Targets`] directive string, e.g. `"my_crate=debug,other=warn"`.
+    Targets(String),
+    /// An [`EnvFilter`] directive string, e.g. `"my_crate=debug,other=warn"`.
+    EnvFilter(String),
+}
+
+impl FilterConfig {
+    fn build(&self) -> Result<Box<dyn Filter<Registry> + Send + Sync>, ConfigError> {
+        match self {
+            FilterConfig::Targets(directives) => directives
+                .parse::<Targets>()
+                .map(|targets| Box::new(targets) as _)
+                .map_err(|e| ConfigError::new("targets", directives, &e)),
+            FilterConfig::EnvFilter(directives) => EnvFilter::try_new(directives)
+                .map(|filter| Box::new(filter) as _)
+                .map_err(|e| ConfigError::new("env_filter", directives, &e)),
+        }
+    }
+}
+
+/// The configuration for a single layer in a [`Config`]'s layer stack.
+#[derive(Clone, Debug, Deserialize)]
+pub struct LayerConfig {
+    /// How events and spans recorded by this layer should be formatted.
+    #[serde(default)]
+    pub format: FormatConfig,
+    /// Where this layer's output should be written.
+    #[serde(default)]
+    pub writer: WriterTarget,
+    /// Whether to use ANSI terminal colors in this layer's output.
+    #[cfg(feature = "ansi")]
+    #[serde(default = "default_ansi")]
+    pub ansi: bool,
+    /// This layer's filter directive, if any. A layer with no filter is
+    /// enabled for every span and event that reaches the collector.
+    #[serde(default)]
+    pub filter: Option<FilterConfig>,
+}
+
+#[cfg(feature = "ansi")]
+fn default_ansi() -> bool {
+    true
+}
+
+impl LayerConfig {
+    fn build(&self) -> Result<Box<dyn Subscribe<Registry> + Send + Sync>, ConfigError> {
+        let subscriber = crate::fmt::subscriber().with_writer(self.writer.into_make_writer());
+        #[cfg(feature = "ansi")]
+        let subscriber = subscriber.with_ansi(self.ansi);
+
+        macro_rules! boxed {
+            ($subscriber:expr) => {{
+                let subscriber = $subscriber;
+                match &self.filter {
+                    Some(filter) => Box::new(subscriber.with_filter(filter.build()?))
+                        as Box<dyn Subscribe<Registry> + Send + Sync>,
+                    None => Box::new(subscriber) as Box<dyn Subscribe<Registry> + Send + Sync>,
+                }
+            }};
+        }
+
+        Ok(match self.format {
+            FormatConfig::Full => boxed!(subscriber),
+            FormatConfig::Compact => boxed!(subscriber.compact()),
+            #[cfg(feature = "json")]
+            FormatConfig::Json => boxed!(subscriber.json()),
+        })
+    }
+}
+
+/// A deserializable description of a full collector's layer stack.
+///
+/// See the [module-level documentation][self] for an example.
+#[derive(Clone, Debug, Deserialize)]
+pub struct Config {
+    /// The layers to assemble into a collector.
+    pub layers: Vec<LayerConfig>,
+}
+
+impl Config {
+    /// Assembles this configuration into a [`Collect`], which can be
+    /// installed with [`tracing::collect::set_global_default`] or
+    /// [`tracing::collect::set_default`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error describing the offending directive if any layer's
+    /// [`FilterConfig`] fails to parse.
+    pub fn build(&self) -> Result<impl Collect + Send + Sync, ConfigError> {
+        let (dynamic, handle) = DynamicSubscriber::<Registry>::new();
+        for layer in &self.layers {
+            handle.add_boxed(layer.build()?);
+        }
+        Ok(crate::registry().with(dynamic))
+    }
+}
+
+/// An error assembling a [`Collect`] from a [`Config`].
+#[derive(Debug)]
+pub struct ConfigError {
+    message: String,
+}
+
+impl ConfigError {
+    fn new(kind: &str, directives: &str, source: &dyn fmt::Display) -> Self {
+        Self {
+            message: format!(
+                "invalid `{}` filter directive {:?}: {}",
+                kind, directives, source
+            ),
+        }
+    }
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builds_a_layer_stack_from_json() {
+        let config: Config = serde_json::from_str(
+            r#"{
+                "layers": [
+                    { "format": "compact", "writer": "stderr", "filter": { "targets": "my_crate=warn" } },
+                    { "format": "full" }
+                ]
+            }"#,
+        )
+        .expect("config should deserialize");
+
+        assert_eq!(config.layers.len(), 2);
+        config.build().expect("config should build a collector");
+    }
+
+    #[test]
+    fn invalid_filter_directive_is_a_descriptive_error() {
+        let config = Config {
+            layers: vec![LayerConfig {
+                format: FormatConfig::Full,
+                writer: WriterTarget::Stdout,
+                #[cfg(feature = "ansi")]
+                ansi: true,
+                filter: Some(FilterConfig::Targets("not a valid directive".to_string())),
+            }],
+        };
+
+        let err = match config.build() {
+            Ok(_) => panic!("directive should fail to parse"),
+            Err(err) => err,
+        };
+        assert!(
+            err.to_string().contains("targets"),
+            "error should mention the offending filter kind: {}",
+            err
+        );
+    }
+}
@@ -0,0 +1,203 @@
+//! A development-mode [`Subscribe`] for tracking which callsites actually
+//! fired during a test run.
+//!
+//! [`CoverageLayer`] records the location of every span and event it sees,
+//! so a test suite can produce a [`Report`] of exactly which of a
+//! codebase's `tracing` callsites were exercised.
+//!
+//! # Limitations
+//!
+//! `tracing`'s callsite registry only ever contains callsites that have
+//! *already fired at least once* -- each one is lazily registered the
+//! first time its macro executes, not at compile time -- so there is no
+//! runtime API for enumerating every `event!`/`span!` invocation that
+//! exists in a codebase, only the ones that have run. [`Report`] can tell
+//! you what fired; producing a genuine "dead instrumentation" report
+//! (what never fired at all) means diffing [`Report::fired`] against a
+//! separately maintained list of expected callsites, e.g. scraped from
+//! source, via [`Report::dead`] -- not something this layer can discover
+//! on its own.
+//!
+//! This module requires the "coverage" feature flag.
+use std::{
+    collections::HashSet,
+    sync::{Arc, Mutex},
+};
+
+use tracing_core::{span, Collect, Event, Metadata};
+
+use crate::registry::LookupSpan;
+use crate::subscribe::{Context, Subscribe};
+
+/// Identifies a callsite by its source location, independent of any one
+/// span or event recorded there.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct CallsiteId {
+    /// The callsite's target.
+    pub target: &'static str,
+    /// The span or event's name.
+    pub name: &'static str,
+    /// The source file the callsite is in, if known.
+    pub file: Option<&'static str>,
+    /// The line within [`file`](Self::file) the callsite is on, if known.
+    pub line: Option<u32>,
+}
+
+impl CallsiteId {
+    fn from_metadata(metadata: &'static Metadata<'static>) -> Self {
+        Self {
+            target: metadata.target(),
+            name: metadata.name(),
+            file: metadata.file(),
+            line: metadata.line(),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct Inner {
+    fired: HashSet<CallsiteId>,
+}
+
+/// A handle for inspecting which callsites a [`CoverageLayer`] has seen
+/// fire.
+///
+/// # Examples
+///
+/// ```
+/// use tracing_subscriber::coverage::CoverageLayer;
+/// use tracing_subscriber::prelude::*;
+///
+/// let (layer, report) = CoverageLayer::new();
+/// let subscriber = tracing_subscriber::registry().with(layer);
+///
+/// tracing::collect::with_default(subscriber, || {
+///     tracing::info_span!("used").in_scope(|| {});
+/// });
+///
+/// assert!(report.has_fired(module_path!(), "used"));
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct Report {
+    inner: Arc<Mutex<Inner>>,
+}
+
+impl Report {
+    /// Returns every callsite recorded as having fired so far.
+    pub fn fired(&self) -> Vec<CallsiteId> {
+        try_lock!(self.inner.lock(), else return Vec::new())
+            .fired
+            .iter()
+            .cloned()
+            .collect()
+    }
+
+    /// Returns `true` if some callsite matching `target` and `name` has
+    /// fired.
+    pub fn has_fired(&self, target: &str, name: &str) -> bool {
+        try_lock!(self.inner.lock(), else return false)
+            .fired
+            .iter()
+            .any(|callsite| callsite.target == target && callsite.name == name)
+    }
+
+    /// Given the callsites expected to exist, returns the ones among them
+    /// that never fired.
+    ///
+    /// There's no runtime way to discover `expected` on its own -- see the
+    /// [module-level docs](self) -- so the caller has to supply it, e.g.
+    /// scraped from source with a separate script.
+    pub fn dead<'a>(&self, expected: impl IntoIterator<Item = &'a CallsiteId>) -> Vec<CallsiteId> {
+        let inner = try_lock!(self.inner.lock(), else return Vec::new());
+        expected
+            .into_iter()
+            .filter(|callsite| !inner.fired.contains(*callsite))
+            .cloned()
+            .collect()
+    }
+}
+
+/// A [`Subscribe`] that records the location of every span and event it
+/// sees, for building a [`Report`] of instrumentation coverage.
+///
+/// See the [module-level documentation][self] for an example and its
+/// limitations.
+#[derive(Clone, Debug, Default)]
+pub struct CoverageLayer {
+    report: Report,
+}
+
+impl CoverageLayer {
+    /// Returns a new `CoverageLayer` and a [`Report`] for inspecting what
+    /// it records.
+    pub fn new() -> (Self, Report) {
+        let report = Report::default();
+        (
+            Self {
+                report: report.clone(),
+            },
+            report,
+        )
+    }
+
+    fn record(&self, metadata: &'static Metadata<'static>) {
+        let mut inner = try_lock!(self.report.inner.lock(), else return);
+        inner.fired.insert(CallsiteId::from_metadata(metadata));
+    }
+}
+
+impl<C> Subscribe<C> for CoverageLayer
+where
+    C: Collect + for<'a> LookupSpan<'a>,
+{
+    fn new_span(&self, attrs: &span::Attributes<'_>, _id: &span::Id, _ctx: Context<'_, C>) {
+        self.record(attrs.metadata());
+    }
+
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, C>) {
+        self.record(event.metadata());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+
+    #[test]
+    fn records_fired_callsites() {
+        let (layer, report) = CoverageLayer::new();
+        let subscriber = crate::registry().with(layer);
+
+        tracing_core::dispatch::with_default(&tracing_core::Dispatch::new(subscriber), || {
+            let _span = tracing::info_span!("exercised").entered();
+            tracing::info!("also exercised");
+        });
+
+        let fired = report.fired();
+        assert!(report.has_fired(module_path!(), "exercised"));
+        assert!(fired.iter().any(|cs| cs.target == module_path!() && cs.name.starts_with("event ")));
+        assert!(!report.has_fired(module_path!(), "never called"));
+    }
+
+    #[test]
+    fn dead_reports_callsites_that_never_fired() {
+        let (layer, report) = CoverageLayer::new();
+        let subscriber = crate::registry().with(layer);
+
+        tracing_core::dispatch::with_default(&tracing_core::Dispatch::new(subscriber), || {
+            tracing::info!("exercised");
+        });
+
+        let exercised = report.fired().into_iter().next().unwrap();
+        let unexercised = CallsiteId {
+            target: "made_up",
+            name: "never_called",
+            file: None,
+            line: None,
+        };
+
+        let dead = report.dead([&exercised, &unexercised]);
+        assert_eq!(dead, vec![unexercised]);
+    }
+}
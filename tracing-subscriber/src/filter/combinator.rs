@@ -0,0 +1,245 @@
+//! Combinators for composing [`Filter`]s.
+//!
+//! This module provides the [`And`], [`Or`], and [`Not`] types, which
+//! implement [`Filter`] by combining the enablement decisions of one or two
+//! other `Filter`s, along with the [`FilterExt`] trait, which adds `.and()`,
+//! `.or()`, and `.not()` combinator methods to any `Filter`.
+//!
+//! Combining filters this way (rather than writing a closure that inlines
+//! the logic) preserves each inner filter's [`callsite_enabled`] and
+//! [`max_level_hint`], so the subscriber stack can still skip disabled
+//! callsites and levels entirely, rather than falling back to per-event
+//! evaluation.
+//!
+//! [`callsite_enabled`]: Filter::callsite_enabled
+//! [`max_level_hint`]: Filter::max_level_hint
+use super::Filter;
+use tracing_core::{span, Interest, LevelFilter, Metadata};
+
+use crate::subscribe::Context;
+
+/// A [`Filter`] that enables a span or event if and only if both of the
+/// wrapped filters would enable it. See [`FilterExt::and`].
+#[derive(Clone, Debug)]
+pub struct And<A, B> {
+    a: A,
+    b: B,
+}
+
+/// A [`Filter`] that enables a span or event if either of the wrapped
+/// filters would enable it. See [`FilterExt::or`].
+#[derive(Clone, Debug)]
+pub struct Or<A, B> {
+    a: A,
+    b: B,
+}
+
+/// A [`Filter`] that inverts the enablement decision of the wrapped filter.
+/// See [`FilterExt::not`].
+#[derive(Clone, Debug)]
+pub struct Not<A> {
+    a: A,
+}
+
+/// Extension trait adding combinator methods to any [`Filter`].
+pub trait FilterExt<C>: Filter<C> {
+    /// Combines `self` with `other`, returning a new [`Filter`] that only
+    /// enables a span or event if *both* filters enable it.
+    fn and<B>(self, other: B) -> And<Self, B>
+    where
+        Self: Sized,
+        B: Filter<C>,
+    {
+        And { a: self, b: other }
+    }
+
+    /// Combines `self` with `other`, returning a new [`Filter`] that enables
+    /// a span or event if *either* filter enables it.
+    fn or<B>(self, other: B) -> Or<Self, B>
+    where
+        Self: Sized,
+        B: Filter<C>,
+    {
+        Or { a: self, b: other }
+    }
+
+    /// Inverts `self`, returning a new [`Filter`] that enables whatever
+    /// `self` disables, and vice versa.
+    fn not(self) -> Not<Self>
+    where
+        Self: Sized,
+    {
+        Not { a: self }
+    }
+
+    /// Erases the concrete type of `self`, returning a boxed [`Filter`]
+    /// trait object.
+    ///
+    /// This is useful when a filter's concrete type can't be named, or
+    /// isn't known until runtime (for instance, when a filter is chosen
+    /// based on configuration loaded from a file).
+    fn boxed(self) -> Box<dyn Filter<C> + Send + Sync + 'static>
+    where
+        Self: Sized + Filter<C> + Send + Sync + 'static,
+    {
+        Box::new(self)
+    }
+}
+
+impl<C, F: Filter<C>> FilterExt<C> for F {}
+
+// === impl And ===
+
+impl<C, A, B> Filter<C> for And<A, B>
+where
+    A: Filter<C>,
+    B: Filter<C>,
+{
+    fn enabled(&self, meta: &Metadata<'_>, cx: &Context<'_, C>) -> bool {
+        self.a.enabled(meta, cx) && self.b.enabled(meta, cx)
+    }
+
+    fn callsite_enabled(&self, meta: &'static Metadata<'static>) -> Interest {
+        let a = self.a.callsite_enabled(meta);
+        let b = self.b.callsite_enabled(meta);
+        if a.is_never() || b.is_never() {
+            Interest::never()
+        } else if a.is_always() && b.is_always() {
+            Interest::always()
+        } else {
+            Interest::sometimes()
+        }
+    }
+
+    fn max_level_hint(&self) -> Option<LevelFilter> {
+        std::cmp::min(self.a.max_level_hint(), self.b.max_level_hint())
+    }
+
+    fn on_new_span(&self, attrs: &span::Attributes<'_>, id: &span::Id, cx: Context<'_, C>) {
+        self.a.on_new_span(attrs, id, cx.clone());
+        self.b.on_new_span(attrs, id, cx);
+    }
+
+    fn on_record(&self, id: &span::Id, values: &span::Record<'_>, cx: Context<'_, C>) {
+        self.a.on_record(id, values, cx.clone());
+        self.b.on_record(id, values, cx);
+    }
+
+    fn on_enter(&self, id: &span::Id, cx: Context<'_, C>) {
+        self.a.on_enter(id, cx.clone());
+        self.b.on_enter(id, cx);
+    }
+
+    fn on_exit(&self, id: &span::Id, cx: Context<'_, C>) {
+        self.a.on_exit(id, cx.clone());
+        self.b.on_exit(id, cx);
+    }
+
+    fn on_close(&self, id: span::Id, cx: Context<'_, C>) {
+        self.a.on_close(id.clone(), cx.clone());
+        self.b.on_close(id, cx);
+    }
+}
+
+// === impl Or ===
+
+impl<C, A, B> Filter<C> for Or<A, B>
+where
+    A: Filter<C>,
+    B: Filter<C>,
+{
+    fn enabled(&self, meta: &Metadata<'_>, cx: &Context<'_, C>) -> bool {
+        self.a.enabled(meta, cx) || self.b.enabled(meta, cx)
+    }
+
+    fn callsite_enabled(&self, meta: &'static Metadata<'static>) -> Interest {
+        let a = self.a.callsite_enabled(meta);
+        let b = self.b.callsite_enabled(meta);
+        if a.is_always() || b.is_always() {
+            Interest::always()
+        } else if a.is_never() && b.is_never() {
+            Interest::never()
+        } else {
+            Interest::sometimes()
+        }
+    }
+
+    fn max_level_hint(&self) -> Option<LevelFilter> {
+        match (self.a.max_level_hint(), self.b.max_level_hint()) {
+            (Some(a), Some(b)) => Some(std::cmp::max(a, b)),
+            // If either side has no hint, it may enable any level, so
+            // neither does the combination.
+            _ => None,
+        }
+    }
+
+    fn on_new_span(&self, attrs: &span::Attributes<'_>, id: &span::Id, cx: Context<'_, C>) {
+        self.a.on_new_span(attrs, id, cx.clone());
+        self.b.on_new_span(attrs, id, cx);
+    }
+
+    fn on_record(&self, id: &span::Id, values: &span::Record<'_>, cx: Context<'_, C>) {
+        self.a.on_record(id, values, cx.clone());
+        self.b.on_record(id, values, cx);
+    }
+
+    fn on_enter(&self, id: &span::Id, cx: Context<'_, C>) {
+        self.a.on_enter(id, cx.clone());
+        self.b.on_enter(id, cx);
+    }
+
+    fn on_exit(&self, id: &span::Id, cx: Context<'_, C>) {
+        self.a.on_exit(id, cx.clone());
+        self.b.on_exit(id, cx);
+    }
+
+    fn on_close(&self, id: span::Id, cx: Context<'_, C>) {
+        self.a.on_close(id.clone(), cx.clone());
+        self.b.on_close(id, cx);
+    }
+}
+
+// === impl Not ===
+
+impl<C, A> Filter<C> for Not<A>
+where
+    A: Filter<C>,
+{
+    fn enabled(&self, meta: &Metadata<'_>, cx: &Context<'_, C>) -> bool {
+        !self.a.enabled(meta, cx)
+    }
+
+    fn callsite_enabled(&self, meta: &'static Metadata<'static>) -> Interest {
+        match self.a.callsite_enabled(meta) {
+            i if i.is_always() => Interest::never(),
+            i if i.is_never() => Interest::always(),
+            _ => Interest::sometimes(),
+        }
+    }
+
+    fn max_level_hint(&self) -> Option<LevelFilter> {
+        // Since `Not` may enable anything the inner filter disables, it
+        // provides no useful upper bound on what it will enable.
+        None
+    }
+
+    fn on_new_span(&self, attrs: &span::Attributes<'_>, id: &span::Id, cx: Context<'_, C>) {
+        self.a.on_new_span(attrs, id, cx);
+    }
+
+    fn on_record(&self, id: &span::Id, values: &span::Record<'_>, cx: Context<'_, C>) {
+        self.a.on_record(id, values, cx);
+    }
+
+    fn on_enter(&self, id: &span::Id, cx: Context<'_, C>) {
+        self.a.on_enter(id, cx);
+    }
+
+    fn on_exit(&self, id: &span::Id, cx: Context<'_, C>) {
+        self.a.on_exit(id, cx);
+    }
+
+    fn on_close(&self, id: span::Id, cx: Context<'_, C>) {
+        self.a.on_close(id, cx);
+    }
+}
@@ -0,0 +1,116 @@
+//! A [`Filter`] that bounds the wrapped subscriber's work to a per-window
+//! budget, so a burst of unusually expensive events can't monopolize the
+//! thread recording them.
+use std::{
+    sync::atomic::{AtomicUsize, Ordering},
+    time::{Duration, Instant},
+};
+
+use tracing_core::{collect::Collect, Metadata};
+
+use super::Filter;
+use crate::subscribe::Context;
+use crate::sync::RwLock;
+
+/// A [`Filter`] that enables the wrapped subscriber only while a bounded
+/// budget of "work units" remains in the current rolling window, skipping
+/// the rest of that window's work once the budget is exhausted.
+///
+/// Unlike [`RateLimit`], which counts events one-for-one, `CooperativeBudget`
+/// weighs each callsite with a caller-supplied `cost` function, so that a
+/// handful of unusually expensive events (for instance, ones with many
+/// fields) count the same as a burst of many cheap ones. This is meant for
+/// wrapping subscribers that do real synchronous work in `on_event`, such as
+/// serializing or formatting the event, where a single enormous event could
+/// otherwise stall the thread recording it.
+///
+/// The number of work units skipped once the budget is exhausted is
+/// available from [`CooperativeBudget::deferred_units`].
+///
+/// [`RateLimit`]: super::RateLimit
+pub struct CooperativeBudget<F> {
+    max_per_window: usize,
+    window: Duration,
+    cost: F,
+    state: RwLock<WindowState>,
+    deferred: AtomicUsize,
+}
+
+#[derive(Debug)]
+struct WindowState {
+    window_start: Instant,
+    spent: usize,
+}
+
+impl<F> std::fmt::Debug for CooperativeBudget<F> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // `cost` is a caller-supplied closure with no `Debug` impl to speak
+        // of; everything else about the budget's state is printed as usual.
+        f.debug_struct("CooperativeBudget")
+            .field("max_per_window", &self.max_per_window)
+            .field("window", &self.window)
+            .field("state", &self.state)
+            .field("deferred", &self.deferred)
+            .finish()
+    }
+}
+
+impl<F> CooperativeBudget<F>
+where
+    F: Fn(&Metadata<'_>) -> usize,
+{
+    /// Returns a new `CooperativeBudget` that allows at most
+    /// `max_per_window` work units, as measured by `cost`, per `window`.
+    pub fn new(max_per_window: usize, window: Duration, cost: F) -> Self {
+        Self {
+            max_per_window,
+            window,
+            cost,
+            state: RwLock::new(WindowState {
+                window_start: Instant::now(),
+                spent: 0,
+            }),
+            deferred: AtomicUsize::new(0),
+        }
+    }
+
+    /// Returns the total number of work units skipped because the budget
+    /// for their window was already exhausted, across all windows since
+    /// this budget was created.
+    pub fn deferred_units(&self) -> usize {
+        self.deferred.load(Ordering::Relaxed)
+    }
+
+    fn spend(&self, units: usize) -> bool {
+        let now = Instant::now();
+        let mut state = try_lock!(self.state.write(), else return true);
+
+        if now.duration_since(state.window_start) >= self.window {
+            state.window_start = now;
+            state.spent = 0;
+        }
+
+        if state.spent + units <= self.max_per_window {
+            state.spent += units;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+impl<C, F> Filter<C> for CooperativeBudget<F>
+where
+    C: Collect,
+    F: Fn(&Metadata<'_>) -> usize,
+{
+    fn enabled(&self, meta: &Metadata<'_>, _cx: &Context<'_, C>) -> bool {
+        let units = (self.cost)(meta);
+        if self.spend(units) {
+            true
+        } else {
+            self.deferred.fetch_add(units, Ordering::Relaxed);
+            false
+        }
+    }
+}
@@ -203,8 +203,8 @@ impl FromStr for Directive {
                     (
                         # field name
                         [[:word:]][[[:word:]]\.]*
-                        # value part (optional)
-                        (?:=[^,]+)?
+                        # comparison operator and value part (optional)
+                        (?:(?:>=|<=|==|!=|>|<|=)[^,]+)?
                     )
                     # trailing comma or EOS
                     (?:,\s?|$)
@@ -1083,6 +1083,57 @@ mod test {
         assert_eq!(dirs[2].in_span, Some("baz".to_string()));
     }
 
+    #[test]
+    fn parse_directives_with_field_comparison() {
+        let dirs = parse_directives("request[{attempt>=3}]=debug");
+        assert_eq!(dirs.len(), 1, "\nparsed: {:#?}", dirs);
+        assert_eq!(dirs[0].target, Some("request".to_string()));
+        assert_eq!(dirs[0].level, LevelFilter::DEBUG);
+        assert_eq!(dirs[0].fields.len(), 1);
+        assert_eq!(dirs[0].fields[0].name, "attempt");
+        assert_eq!(
+            dirs[0].fields[0].value,
+            Some(field::ValueMatch::Cmp(
+                field::CompareOp::Ge,
+                field::NumMatch::U64(3)
+            ))
+        );
+    }
+
+    #[test]
+    fn parse_directives_with_field_equality() {
+        let dirs = parse_directives("request[{attempt==3}]=debug");
+        assert_eq!(dirs.len(), 1, "\nparsed: {:#?}", dirs);
+        assert_eq!(dirs[0].target, Some("request".to_string()));
+        assert_eq!(dirs[0].level, LevelFilter::DEBUG);
+        assert_eq!(dirs[0].fields.len(), 1);
+        assert_eq!(dirs[0].fields[0].name, "attempt");
+        assert_eq!(
+            dirs[0].fields[0].value,
+            Some(field::ValueMatch::Cmp(
+                field::CompareOp::Eq,
+                field::NumMatch::U64(3)
+            ))
+        );
+    }
+
+    #[test]
+    fn parse_directives_with_field_inequality() {
+        let dirs = parse_directives("request[{attempt!=3}]=debug");
+        assert_eq!(dirs.len(), 1, "\nparsed: {:#?}", dirs);
+        assert_eq!(dirs[0].target, Some("request".to_string()));
+        assert_eq!(dirs[0].level, LevelFilter::DEBUG);
+        assert_eq!(dirs[0].fields.len(), 1);
+        assert_eq!(dirs[0].fields[0].name, "attempt");
+        assert_eq!(
+            dirs[0].fields[0].value,
+            Some(field::ValueMatch::Cmp(
+                field::CompareOp::Ne,
+                field::NumMatch::U64(3)
+            ))
+        );
+    }
+
     #[test]
     fn parse_directives_with_dash_in_target_name() {
         let dirs = parse_directives("target-name=info");
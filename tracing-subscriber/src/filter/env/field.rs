@@ -44,6 +44,114 @@ pub(crate) enum ValueMatch {
     I64(i64),
     NaN,
     Pat(Box<MatchPattern>),
+    Cmp(CompareOp, NumMatch),
+}
+
+/// A numeric comparison operator used by a [`ValueMatch::Cmp`] directive,
+/// e.g. the `>=` in `[request{attempt>=3}]=debug`.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub(crate) enum CompareOp {
+    Gt,
+    Ge,
+    Lt,
+    Le,
+    Eq,
+    Ne,
+}
+
+impl CompareOp {
+    fn matches<T: PartialOrd>(self, actual: T, expected: T) -> bool {
+        match self {
+            CompareOp::Gt => actual > expected,
+            CompareOp::Ge => actual >= expected,
+            CompareOp::Lt => actual < expected,
+            CompareOp::Le => actual <= expected,
+            CompareOp::Eq => actual == expected,
+            CompareOp::Ne => actual != expected,
+        }
+    }
+}
+
+impl fmt::Display for CompareOp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            CompareOp::Gt => ">",
+            CompareOp::Ge => ">=",
+            CompareOp::Lt => "<",
+            CompareOp::Le => "<=",
+            CompareOp::Eq => "==",
+            CompareOp::Ne => "!=",
+        })
+    }
+}
+
+/// The numeric value compared against by a [`ValueMatch::Cmp`] directive.
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum NumMatch {
+    F64(f64),
+    U64(u64),
+    I64(i64),
+}
+
+impl PartialEq for NumMatch {
+    fn eq(&self, other: &Self) -> bool {
+        use NumMatch::*;
+        match (self, other) {
+            (F64(a), F64(b)) => (a - b).abs() < f64::EPSILON,
+            (U64(a), U64(b)) => a.eq(b),
+            (I64(a), I64(b)) => a.eq(b),
+            _ => false,
+        }
+    }
+}
+
+impl Eq for NumMatch {}
+
+impl PartialOrd for NumMatch {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for NumMatch {
+    fn cmp(&self, other: &Self) -> Ordering {
+        fn as_f64(v: &NumMatch) -> f64 {
+            match *v {
+                NumMatch::F64(v) => v,
+                NumMatch::U64(v) => v as f64,
+                NumMatch::I64(v) => v as f64,
+            }
+        }
+        as_f64(self)
+            .partial_cmp(&as_f64(other))
+            .unwrap_or(Ordering::Equal)
+    }
+}
+
+impl fmt::Display for NumMatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NumMatch::F64(ref inner) => fmt::Display::fmt(inner, f),
+            NumMatch::U64(ref inner) => fmt::Display::fmt(inner, f),
+            NumMatch::I64(ref inner) => fmt::Display::fmt(inner, f),
+        }
+    }
+}
+
+impl FromStr for NumMatch {
+    type Err = Box<dyn Error + Send + Sync>;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Ok(value) = s.parse::<u64>() {
+            return Ok(NumMatch::U64(value));
+        }
+        if let Ok(value) = s.parse::<i64>() {
+            return Ok(NumMatch::I64(value));
+        }
+        if let Ok(value) = s.parse::<f64>() {
+            return Ok(NumMatch::F64(value));
+        }
+        Err(format!("`{}` is not a number, but comparison operators only support numeric field values", s).into())
+    }
 }
 
 impl Eq for ValueMatch {}
@@ -63,6 +171,7 @@ impl PartialEq for ValueMatch {
             (I64(a), I64(b)) => a.eq(b),
             (NaN, NaN) => true,
             (Pat(a), Pat(b)) => a.eq(b),
+            (Cmp(op_a, a), Cmp(op_b, b)) => op_a.eq(op_b) && a.eq(b),
             _ => false,
         }
     }
@@ -96,11 +205,37 @@ impl Ord for ValueMatch {
             (I64(_), _) => Ordering::Less,
 
             (Pat(this), Pat(that)) => this.cmp(that),
+            (Pat(_), Cmp(..)) => Ordering::Less,
             (Pat(_), _) => Ordering::Greater,
+
+            (Cmp(op_a, a), Cmp(op_b, b)) => op_a.cmp(op_b).then_with(|| a.cmp(b)),
+            (Cmp(..), _) => Ordering::Greater,
         }
     }
 }
 
+impl PartialOrd for CompareOp {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for CompareOp {
+    fn cmp(&self, other: &Self) -> Ordering {
+        fn rank(op: &CompareOp) -> u8 {
+            match op {
+                CompareOp::Gt => 0,
+                CompareOp::Ge => 1,
+                CompareOp::Lt => 2,
+                CompareOp::Le => 3,
+                CompareOp::Eq => 4,
+                CompareOp::Ne => 5,
+            }
+        }
+        rank(self).cmp(&rank(other))
+    }
+}
+
 impl PartialOrd for ValueMatch {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
         Some(self.cmp(other))
@@ -124,15 +259,43 @@ pub struct BadName {
 impl FromStr for Match {
     type Err = Box<dyn Error + Send + Sync>;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut parts = s.split('=');
-        let name = parts
-            .next()
-            .ok_or_else(|| BadName {
-                name: "".to_string(),
-            })?
-            // TODO: validate field name
-            .to_string();
-        let value = parts.next().map(ValueMatch::from_str).transpose()?;
+        // Find the earliest comparison or equality operator, preferring the
+        // two-character forms (`>=`, `<=`, `==`, `!=`) over their
+        // one-character prefixes so that e.g. `attempt>=3` isn't split as
+        // `attempt>` and `=3`.
+        let op = s.find(|c| matches!(c, '>' | '<' | '=' | '!')).map(|idx| {
+            let rest = &s[idx..];
+            if rest.starts_with(">=") {
+                (idx, 2, Some(CompareOp::Ge))
+            } else if rest.starts_with("<=") {
+                (idx, 2, Some(CompareOp::Le))
+            } else if rest.starts_with("==") {
+                (idx, 2, Some(CompareOp::Eq))
+            } else if rest.starts_with("!=") {
+                (idx, 2, Some(CompareOp::Ne))
+            } else if rest.starts_with('>') {
+                (idx, 1, Some(CompareOp::Gt))
+            } else if rest.starts_with('<') {
+                (idx, 1, Some(CompareOp::Lt))
+            } else {
+                (idx, 1, None)
+            }
+        });
+
+        let (name, value) = match op {
+            Some((idx, len, Some(op))) => {
+                let name = s[..idx].to_string();
+                let value = NumMatch::from_str(&s[idx + len..])?;
+                (name, Some(ValueMatch::Cmp(op, value)))
+            }
+            Some((idx, len, None)) => {
+                let name = s[..idx].to_string();
+                let value = ValueMatch::from_str(&s[idx + len..])?;
+                (name, Some(value))
+            }
+            None => (s.to_string(), None),
+        };
+
         Ok(Match { name, value })
     }
 }
@@ -222,6 +385,7 @@ impl fmt::Display for ValueMatch {
             ValueMatch::I64(ref inner) => fmt::Display::fmt(inner, f),
             ValueMatch::U64(ref inner) => fmt::Display::fmt(inner, f),
             ValueMatch::Pat(ref inner) => fmt::Display::fmt(inner, f),
+            ValueMatch::Cmp(op, ref inner) => write!(f, "{}{}", op, inner),
         }
     }
 }
@@ -348,6 +512,18 @@ impl SpanMatch {
     }
 }
 
+/// Evaluates a [`CompareOp`] against a [`NumMatch`] expectation and an
+/// actual numeric value, coercing both sides to `f64` so e.g. an integer
+/// field can be compared against a directive like `attempt>=3.5`.
+fn cmp_matches(op: CompareOp, expected: &NumMatch, actual: f64) -> bool {
+    let expected = match *expected {
+        NumMatch::F64(v) => v,
+        NumMatch::U64(v) => v as f64,
+        NumMatch::I64(v) => v as f64,
+    };
+    op.matches(actual, expected)
+}
+
 impl<'a> Visit for MatchVisitor<'a> {
     fn record_f64(&mut self, field: &Field, value: f64) {
         match self.inner.fields.get(field) {
@@ -357,6 +533,9 @@ impl<'a> Visit for MatchVisitor<'a> {
             Some((ValueMatch::F64(ref e), ref matched)) if (value - *e).abs() < f64::EPSILON => {
                 matched.store(true, Release);
             }
+            Some((ValueMatch::Cmp(op, ref e), ref matched)) if cmp_matches(*op, e, value) => {
+                matched.store(true, Release);
+            }
             _ => {}
         }
     }
@@ -371,6 +550,11 @@ impl<'a> Visit for MatchVisitor<'a> {
             Some((ValueMatch::U64(ref e), ref matched)) if Ok(value) == (*e).try_into() => {
                 matched.store(true, Release);
             }
+            Some((ValueMatch::Cmp(op, ref e), ref matched))
+                if cmp_matches(*op, e, value as f64) =>
+            {
+                matched.store(true, Release);
+            }
             _ => {}
         }
     }
@@ -380,6 +564,11 @@ impl<'a> Visit for MatchVisitor<'a> {
             Some((ValueMatch::U64(ref e), ref matched)) if value == *e => {
                 matched.store(true, Release);
             }
+            Some((ValueMatch::Cmp(op, ref e), ref matched))
+                if cmp_matches(*op, e, value as f64) =>
+            {
+                matched.store(true, Release);
+            }
             _ => {}
         }
     }
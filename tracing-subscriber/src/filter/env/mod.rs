@@ -54,7 +54,11 @@ use tracing_core::{
 ///    For example: `[span{field=\"value\"}]=debug`, `[{field}]=trace`.
 /// - `value` matches on the value of a span's field. If a value is a numeric literal or a bool,
 ///    it will match _only_ on that value. Otherwise, this filter acts as a regex on
-///    the `std::fmt::Debug` output from the value.
+///    the `std::fmt::Debug` output from the value. A numeric field value may also be
+///    preceded by a comparison operator (`>`, `>=`, `<`, or `<=`) instead of `=`, in which
+///    case the directive matches whenever the recorded value satisfies that comparison.
+///    For example: `[request{attempt>=3}]=debug` only enables `debug` once a `request`
+///    span's `attempt` field reaches `3`.
 /// - `level` sets a maximum verbosity level accepted by this directive.
 ///
 /// ## Usage Notes
@@ -105,8 +109,14 @@ pub struct EnvFilter {
     by_cs: RwLock<HashMap<callsite::Identifier, directive::CallsiteMatcher>>,
 }
 
+// Tracks the per-thread span scope's level filters, keyed by the identity of
+// the `EnvFilter` instance that pushed them. This is a `HashMap` rather than
+// a single `Vec` so that when more than one `EnvFilter` is active at once
+// (e.g. several `Filtered<_, EnvFilter, _>`s in the same subscriber stack),
+// each filter's notion of "what's the most verbose level enabled by the
+// current span scope" doesn't leak into the others'.
 thread_local! {
-    static SCOPE: RefCell<Vec<LevelFilter>> = RefCell::new(Vec::new());
+    static SCOPE: RefCell<HashMap<usize, Vec<LevelFilter>>> = RefCell::new(HashMap::new());
 }
 
 type FieldMap<T> = HashMap<Field, T>;
@@ -362,8 +372,8 @@ impl EnvFilter {
     }
 }
 
-impl<C: Collect> Subscribe<C> for EnvFilter {
-    fn register_callsite(&self, metadata: &'static Metadata<'static>) -> Interest {
+impl EnvFilter {
+    fn register_callsite_inner(&self, metadata: &'static Metadata<'static>) -> Interest {
         if self.has_dynamics && metadata.is_span() {
             // If this metadata describes a span, first, check if there is a
             // dynamic filter that should be constructed for it. If so, it
@@ -383,7 +393,7 @@ impl<C: Collect> Subscribe<C> for EnvFilter {
         }
     }
 
-    fn max_level_hint(&self) -> Option<LevelFilter> {
+    fn max_level_hint_inner(&self) -> Option<LevelFilter> {
         if self.dynamics.has_value_filters() {
             // If we perform any filtering on span field *values*, we will
             // enable *all* spans, because their field values are not known
@@ -396,7 +406,7 @@ impl<C: Collect> Subscribe<C> for EnvFilter {
         )
     }
 
-    fn enabled(&self, metadata: &Metadata<'_>, _: Context<'_, C>) -> bool {
+    fn enabled_inner(&self, metadata: &Metadata<'_>) -> bool {
         let level = metadata.level();
 
         // is it possible for a dynamic filter directive to enable this event?
@@ -417,7 +427,12 @@ impl<C: Collect> Subscribe<C> for EnvFilter {
             }
 
             let enabled_by_scope = SCOPE.with(|scope| {
-                for filter in scope.borrow().iter() {
+                let scope = scope.borrow();
+                let stack = match scope.get(&self.id()) {
+                    Some(stack) => stack,
+                    None => return false,
+                };
+                for filter in stack.iter() {
                     if filter >= level {
                         return true;
                     }
@@ -439,7 +454,7 @@ impl<C: Collect> Subscribe<C> for EnvFilter {
         false
     }
 
-    fn new_span(&self, attrs: &span::Attributes<'_>, id: &span::Id, _: Context<'_, C>) {
+    fn new_span_inner(&self, attrs: &span::Attributes<'_>, id: &span::Id) {
         let by_cs = try_lock!(self.by_cs.read());
         if let Some(cs) = by_cs.get(&attrs.metadata().callsite()) {
             let span = cs.to_span_match(attrs);
@@ -447,35 +462,122 @@ impl<C: Collect> Subscribe<C> for EnvFilter {
         }
     }
 
-    fn on_record(&self, id: &span::Id, values: &span::Record<'_>, _: Context<'_, C>) {
+    fn on_record_inner(&self, id: &span::Id, values: &span::Record<'_>) {
         if let Some(span) = try_lock!(self.by_id.read()).get(id) {
             span.record_update(values);
         }
     }
 
-    fn on_enter(&self, id: &span::Id, _: Context<'_, C>) {
+    fn on_enter_inner(&self, id: &span::Id) {
         // XXX: This is where _we_ could push IDs to the stack instead, and use
         // that to allow changing the filter while a span is already entered.
         // But that might be much less efficient...
         if let Some(span) = try_lock!(self.by_id.read()).get(id) {
-            SCOPE.with(|scope| scope.borrow_mut().push(span.level()));
+            SCOPE.with(|scope| {
+                scope
+                    .borrow_mut()
+                    .entry(self.id())
+                    .or_insert_with(Vec::new)
+                    .push(span.level())
+            });
         }
     }
 
-    fn on_exit(&self, id: &span::Id, _: Context<'_, C>) {
+    fn on_exit_inner(&self, id: &span::Id) {
         if self.cares_about_span(id) {
-            SCOPE.with(|scope| scope.borrow_mut().pop());
+            SCOPE.with(|scope| {
+                if let Some(stack) = scope.borrow_mut().get_mut(&self.id()) {
+                    stack.pop();
+                }
+            });
         }
     }
 
-    fn on_close(&self, id: span::Id, _: Context<'_, C>) {
+    /// Returns an identifier unique to this `EnvFilter` instance, used to key
+    /// per-thread span-scope state so that multiple `EnvFilter`s active in
+    /// the same subscriber stack (e.g. as several `Filtered<_, EnvFilter,
+    /// _>`s) don't observe each other's span scopes.
+    fn id(&self) -> usize {
+        self as *const Self as usize
+    }
+
+    fn on_close_inner(&self, id: &span::Id) {
         // If we don't need to acquire a write lock, avoid doing so.
-        if !self.cares_about_span(&id) {
+        if !self.cares_about_span(id) {
             return;
         }
 
         let mut spans = try_lock!(self.by_id.write());
-        spans.remove(&id);
+        spans.remove(id);
+    }
+}
+
+impl<C: Collect> Subscribe<C> for EnvFilter {
+    fn register_callsite(&self, metadata: &'static Metadata<'static>) -> Interest {
+        self.register_callsite_inner(metadata)
+    }
+
+    fn max_level_hint(&self) -> Option<LevelFilter> {
+        self.max_level_hint_inner()
+    }
+
+    fn enabled(&self, metadata: &Metadata<'_>, _: Context<'_, C>) -> bool {
+        self.enabled_inner(metadata)
+    }
+
+    fn new_span(&self, attrs: &span::Attributes<'_>, id: &span::Id, _: Context<'_, C>) {
+        self.new_span_inner(attrs, id)
+    }
+
+    fn on_record(&self, id: &span::Id, values: &span::Record<'_>, _: Context<'_, C>) {
+        self.on_record_inner(id, values)
+    }
+
+    fn on_enter(&self, id: &span::Id, _: Context<'_, C>) {
+        self.on_enter_inner(id)
+    }
+
+    fn on_exit(&self, id: &span::Id, _: Context<'_, C>) {
+        self.on_exit_inner(id)
+    }
+
+    fn on_close(&self, id: span::Id, _: Context<'_, C>) {
+        self.on_close_inner(&id)
+    }
+}
+
+#[cfg(feature = "registry")]
+impl<C: Collect> crate::filter::Filter<C> for EnvFilter {
+    fn callsite_enabled(&self, metadata: &'static Metadata<'static>) -> Interest {
+        self.register_callsite_inner(metadata)
+    }
+
+    fn max_level_hint(&self) -> Option<LevelFilter> {
+        self.max_level_hint_inner()
+    }
+
+    fn enabled(&self, metadata: &Metadata<'_>, _: &Context<'_, C>) -> bool {
+        self.enabled_inner(metadata)
+    }
+
+    fn on_new_span(&self, attrs: &span::Attributes<'_>, id: &span::Id, _: Context<'_, C>) {
+        self.new_span_inner(attrs, id)
+    }
+
+    fn on_record(&self, id: &span::Id, values: &span::Record<'_>, _: Context<'_, C>) {
+        self.on_record_inner(id, values)
+    }
+
+    fn on_enter(&self, id: &span::Id, _: Context<'_, C>) {
+        self.on_enter_inner(id)
+    }
+
+    fn on_exit(&self, id: &span::Id, _: Context<'_, C>) {
+        self.on_exit_inner(id)
+    }
+
+    fn on_close(&self, id: span::Id, _: Context<'_, C>) {
+        self.on_close_inner(&id)
     }
 }
 
@@ -676,6 +778,25 @@ mod tests {
         assert!(interest.is_always());
     }
 
+    #[test]
+    fn callsite_enabled_includes_span_directive_field_comparison() {
+        let filter =
+            EnvFilter::new("app[request{attempt>=3}]=debug").with_collector(NoCollector);
+        static META: &Metadata<'static> = &Metadata::new(
+            "request",
+            "app",
+            Level::TRACE,
+            None,
+            None,
+            None,
+            FieldSet::new(&["attempt"], identify_callsite!(&Cs)),
+            Kind::SPAN,
+        );
+
+        let interest = filter.register_callsite(META);
+        assert!(interest.is_always());
+    }
+
     #[test]
     fn callsite_enabled_includes_span_directive_multiple_fields() {
         let filter = EnvFilter::new("app[mySpan{field=\"value\",field2=2}]=debug")
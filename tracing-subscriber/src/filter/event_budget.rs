@@ -0,0 +1,235 @@
+//! A [`Filter`] that caps how many events a span subtree may record.
+use std::cell::Cell;
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
+
+use tracing_core::{collect::Collect, span, Event, Metadata};
+
+use super::Filter;
+use crate::registry::LookupSpan;
+use crate::subscribe::Context;
+
+/// The target used for the summary event [`EventBudget`] emits when a span
+/// subtree's budget is exceeded, so that event can be recognized (and
+/// exempted from budgeting itself).
+const SUMMARY_TARGET: &str = "tracing_subscriber::filter::event_budget";
+
+thread_local! {
+    // `Filtered` asks a wrapped `Filter` whether an event is enabled more
+    // than once per event (once to decide whether to dispatch it at all, and
+    // again just before actually delivering it), so a naive per-call
+    // increment would consume the budget faster than real events arrive.
+    // This caches the decision made for the first of those calls, so the
+    // second call replays it instead of counting the event twice.
+    //
+    // A `false` decision is never cached: `Filtered::event_enabled` `&&`s
+    // this filter's decision with the wrapped subscriber's, so a `false`
+    // here short-circuits the whole thing and the confirming second call
+    // never happens. Caching it anyway would leave it behind forever,
+    // ready to be replayed against a completely unrelated later event --
+    // this used to be keyed by the event's `'static` callsite metadata
+    // pointer, which is identical for every event recorded at that source
+    // line, so a leftover `false` from one span subtree's exhausted budget
+    // was silently replayed as the *first* decision for an entirely
+    // different, freshly-created subtree that had recorded nothing yet.
+    //
+    // A `true` decision does have a confirming call to wait for, so it's
+    // cached, keyed by the `EventBudget`'s address together with the
+    // *address of the `Event` itself* (a fresh stack value per call, unlike
+    // its callsite) so an unrelated call that happens to reuse the same
+    // address is detected and falls through to a fresh decision instead of
+    // matching it.
+    static PENDING: Cell<Option<(usize, usize, bool)>> = Cell::new(None);
+}
+
+/// A [`Filter`] that caps the number of events a single span subtree may
+/// record, dropping the rest.
+///
+/// The budget is shared by a root span and every span nested inside it: once
+/// the subtree has recorded `capacity` events, further events anywhere in
+/// that subtree are dropped, and a single summary event is emitted
+/// reporting that the budget was exceeded. This protects a sink from a
+/// single pathological request that logs in a tight loop, without affecting
+/// events recorded by other, unrelated span subtrees.
+///
+/// Emitting the summary event requires the `tracing` feature; without it,
+/// events over budget are still dropped, but no summary is emitted.
+///
+/// `EventBudget` only limits events; it has no effect on spans, which are
+/// always enabled, so the subtree's shape is never affected by its own
+/// logging volume.
+#[derive(Clone, Debug)]
+pub struct EventBudget {
+    capacity: usize,
+}
+
+impl EventBudget {
+    /// Returns a new `EventBudget` that allows each span subtree to record
+    /// up to `capacity` events before dropping the rest.
+    pub fn new(capacity: usize) -> Self {
+        Self { capacity }
+    }
+
+    fn check<C>(&self, event: &Event<'_>, cx: &Context<'_, C>) -> bool
+    where
+        C: Collect + for<'span> LookupSpan<'span>,
+    {
+        if event.metadata().target() == SUMMARY_TARGET {
+            return true;
+        }
+
+        let used = match cx
+            .lookup_current()
+            .and_then(|span| span.extensions().get::<SubtreeUsed>().cloned())
+        {
+            Some(used) => used,
+            None => return true,
+        };
+
+        let used = used.0.fetch_add(1, Ordering::Relaxed) + 1;
+        if used <= self.capacity {
+            return true;
+        }
+
+        #[cfg(feature = "tracing")]
+        if used == self.capacity + 1 {
+            tracing::warn!(
+                target: SUMMARY_TARGET,
+                budget = self.capacity,
+                "dropping further events from this span: budget of {} events exceeded",
+                self.capacity,
+            );
+        }
+
+        false
+    }
+}
+
+/// The number of events recorded so far by a span subtree, shared by every
+/// span in that subtree.
+#[derive(Clone)]
+struct SubtreeUsed(Arc<AtomicUsize>);
+
+impl<C> Filter<C> for EventBudget
+where
+    C: Collect + for<'span> LookupSpan<'span>,
+{
+    fn enabled(&self, _meta: &Metadata<'_>, _cx: &Context<'_, C>) -> bool {
+        // The real decision requires an `Event`, since it counts against a
+        // span's shared budget rather than the callsite; always return
+        // `true` here so that `event_enabled` below gets a chance to run.
+        true
+    }
+
+    fn event_enabled(&self, event: &Event<'_>, cx: &Context<'_, C>) -> bool {
+        let key = (
+            self as *const Self as usize,
+            event as *const Event<'_> as *const () as usize,
+        );
+        if let Some((self_ptr, event_ptr, decided)) = PENDING.with(|pending| pending.take()) {
+            if (self_ptr, event_ptr) == key {
+                return decided;
+            }
+            // A leftover decision for a different filter or event -- the
+            // event it belonged to was dropped before its confirming call
+            // arrived. Discard it and fall through to a fresh decision.
+        }
+
+        let enabled = self.check(event, cx);
+        // Only a `true` decision has a confirming second call to look
+        // forward to: `Filtered::event_enabled` short-circuits on `false`,
+        // so `Filtered::on_event` (and the second `event_enabled` call
+        // inside it) never runs for this event at all. Not caching `false`
+        // means there's nothing left behind for a later, unrelated event to
+        // collide with, even if it reuses this `Event`'s stack address.
+        if enabled {
+            PENDING.with(|pending| pending.set(Some((key.0, key.1, enabled))));
+        }
+        enabled
+    }
+
+    fn on_new_span(&self, attrs: &span::Attributes<'_>, id: &span::Id, cx: Context<'_, C>) {
+        let parent = if attrs.is_root() {
+            None
+        } else if let Some(parent) = attrs.parent() {
+            Some(parent.clone())
+        } else {
+            cx.current_span().id().cloned()
+        };
+
+        let used = parent
+            .and_then(|parent| cx.span(&parent))
+            .and_then(|parent| parent.extensions().get::<SubtreeUsed>().cloned())
+            .unwrap_or_else(|| SubtreeUsed(Arc::new(AtomicUsize::new(0))));
+
+        if let Some(span) = cx.span(id) {
+            span.extensions_mut().insert(used);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+    use std::sync::Mutex as StdMutex;
+    use tracing_core::field::{Field, Visit};
+
+    #[derive(Clone, Default)]
+    struct Recorder {
+        seen: Arc<StdMutex<Vec<i64>>>,
+    }
+
+    struct RecordI(Option<i64>);
+
+    impl Visit for RecordI {
+        fn record_i64(&mut self, field: &Field, value: i64) {
+            if field.name() == "i" {
+                self.0 = Some(value);
+            }
+        }
+        fn record_debug(&mut self, _field: &Field, _value: &dyn std::fmt::Debug) {}
+    }
+
+    impl<C> crate::subscribe::Subscribe<C> for Recorder
+    where
+        C: Collect + for<'span> LookupSpan<'span>,
+    {
+        fn on_event(&self, event: &Event<'_>, _cx: Context<'_, C>) {
+            let mut visitor = RecordI(None);
+            event.record(&mut visitor);
+            if let Some(i) = visitor.0 {
+                self.seen.lock().unwrap().push(i);
+            }
+        }
+    }
+
+    fn fire_five(subtree: &str) {
+        let span = tracing::info_span!("subtree", subtree);
+        let _guard = span.enter();
+        for i in 0..5i64 {
+            tracing::info!(i, "event");
+        }
+    }
+
+    #[test]
+    fn separate_subtrees_each_get_their_own_budget() {
+        let recorder = Recorder::default();
+        let seen = recorder.seen.clone();
+        let filter = EventBudget::new(2);
+        let subscriber = crate::registry().with(recorder.with_filter(filter));
+
+        tracing_core::dispatch::with_default(&tracing_core::Dispatch::new(subscriber), || {
+            fire_five("a");
+            fire_five("b");
+        });
+
+        // Each sibling root span has its own budget, so both should
+        // independently deliver their own first two events -- subtree "b"
+        // must not inherit a stale decision left behind by "a" exhausting
+        // its own, unrelated budget at the same callsite.
+        assert_eq!(&*seen.lock().unwrap(), &[0, 1, 0, 1]);
+    }
+}
@@ -0,0 +1,90 @@
+//! A [`Filter`] that lets library authors pin the [`Interest`] reported for
+//! specific targets, independently of whatever `enabled`-based filtering is
+//! also in effect.
+use tracing_core::{collect::Collect, Interest, Metadata};
+
+use super::Filter;
+use crate::subscribe::Context;
+
+/// A [`Filter`] that overrides the [`Interest`] reported for callsites whose
+/// target matches one of its configured prefixes, while otherwise enabling
+/// everything (its [`enabled`](Filter::enabled) always returns `true`).
+///
+/// This is meant to be composed with other [`Filter`]s (see [`FilterExt`])
+/// rather than used on its own, and addresses two opposite needs for
+/// library authors embedding `tracing` in a host application:
+///
+/// - [`pin_dynamic`](InterestPin::pin_dynamic) declares that a target's
+///   callsites should never be cached as [`Interest::always`], even if
+///   every filter in the stack would otherwise agree to always enable them.
+///   This is useful when a callsite's enablement can change at runtime in a
+///   way the interest cache wouldn't otherwise notice --- for example, a
+///   per-thread or per-request override.
+/// - [`pin_always`](InterestPin::pin_always) declares that a target's
+///   callsites are always of interest to *this* filter, which is useful to
+///   document diagnostic or audit events this filter's `enabled` will never
+///   itself disable, so the rest of the filter stack doesn't pay for a
+///   dynamic check on `this` filter's account.
+///
+/// Note that because each [`Subscribe`](crate::Subscribe) in a stack can
+/// only *veto* a shared [`Interest::always`] decision (by returning
+/// something less permissive), not unilaterally grant one over the other
+/// subscribers in the stack, [`pin_always`](InterestPin::pin_always) only
+/// guarantees an always-on callsite when this filter is the only one (or
+/// the least restrictive one) considering that target; it does not override
+/// a `never` or `sometimes` reported by another filter in the same stack.
+/// [`pin_dynamic`](InterestPin::pin_dynamic), on the other hand, is
+/// effective regardless of where in the stack this filter sits, since
+/// `Interest::sometimes` from any one filter forces re-evaluation.
+///
+/// [`FilterExt`]: super::FilterExt
+#[derive(Clone, Debug, Default)]
+pub struct InterestPin {
+    always: Vec<String>,
+    dynamic: Vec<String>,
+}
+
+impl InterestPin {
+    /// Returns a new `InterestPin` with no pinned targets.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Pins every callsite whose target starts with `target` as always of
+    /// interest to this filter.
+    pub fn pin_always(mut self, target: impl Into<String>) -> Self {
+        self.always.push(target.into());
+        self
+    }
+
+    /// Pins every callsite whose target starts with `target` as never
+    /// eligible for [`Interest::always`], forcing it to be re-evaluated via
+    /// `enabled` every time it is hit.
+    pub fn pin_dynamic(mut self, target: impl Into<String>) -> Self {
+        self.dynamic.push(target.into());
+        self
+    }
+
+    fn matches(pins: &[String], target: &str) -> bool {
+        pins.iter().any(|pin| target.starts_with(pin.as_str()))
+    }
+}
+
+impl<C> Filter<C> for InterestPin
+where
+    C: Collect,
+{
+    fn enabled(&self, _meta: &Metadata<'_>, _cx: &Context<'_, C>) -> bool {
+        true
+    }
+
+    fn callsite_enabled(&self, meta: &'static Metadata<'static>) -> Interest {
+        if Self::matches(&self.dynamic, meta.target()) {
+            Interest::sometimes()
+        } else if Self::matches(&self.always, meta.target()) {
+            Interest::always()
+        } else {
+            Interest::sometimes()
+        }
+    }
+}
@@ -25,3 +25,106 @@ impl<C: Collect> crate::Subscribe<C> for LevelFilter {
         (*self).into()
     }
 }
+
+#[cfg(feature = "registry")]
+impl<C: Collect> crate::filter::Filter<C> for LevelFilter {
+    fn enabled(&self, metadata: &Metadata<'_>, _: &crate::subscribe::Context<'_, C>) -> bool {
+        self >= metadata.level()
+    }
+
+    fn callsite_enabled(&self, metadata: &'static Metadata<'static>) -> Interest {
+        if self >= metadata.level() {
+            Interest::always()
+        } else {
+            Interest::never()
+        }
+    }
+
+    fn max_level_hint(&self) -> Option<LevelFilter> {
+        (*self).into()
+    }
+}
+
+std::thread_local! {
+    static THREAD_LEVEL: std::cell::Cell<Option<LevelFilter>> = std::cell::Cell::new(None);
+}
+
+/// A [`Filter`](crate::filter::Filter)/[`Subscribe`](crate::Subscribe) that
+/// enables spans and events up to `default`, but can be temporarily
+/// overridden for the current thread with [`set_thread_level`].
+///
+/// This is useful for cases like "while handling this one request, log at
+/// `TRACE` on this thread, without changing the level for the rest of the
+/// process".
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct PerThreadLevelFilter {
+    default: LevelFilter,
+}
+
+impl PerThreadLevelFilter {
+    /// Returns a new filter that enables spans and events up to `default`
+    /// on threads which have not called [`set_thread_level`].
+    pub fn new(default: LevelFilter) -> Self {
+        Self { default }
+    }
+
+    fn current_level(&self) -> LevelFilter {
+        THREAD_LEVEL.with(|level| level.get()).unwrap_or(self.default)
+    }
+}
+
+/// Overrides the level enabled by any [`PerThreadLevelFilter`] for the
+/// current thread, returning a guard that restores the previous override (or
+/// lack thereof) when dropped.
+pub fn set_thread_level(level: LevelFilter) -> ThreadLevelGuard {
+    let previous = THREAD_LEVEL.with(|cell| cell.replace(Some(level)));
+    ThreadLevelGuard { previous }
+}
+
+/// Restores the previous per-thread level override when dropped. See
+/// [`set_thread_level`].
+#[derive(Debug)]
+pub struct ThreadLevelGuard {
+    previous: Option<LevelFilter>,
+}
+
+impl Drop for ThreadLevelGuard {
+    fn drop(&mut self) {
+        THREAD_LEVEL.with(|cell| cell.set(self.previous));
+    }
+}
+
+impl<C: Collect> crate::Subscribe<C> for PerThreadLevelFilter {
+    fn register_callsite(&self, metadata: &'static Metadata<'static>) -> Interest {
+        // Since the enabled level can change per-thread, we can't decide
+        // this once per callsite; always re-check in `enabled`.
+        let _ = metadata;
+        Interest::sometimes()
+    }
+
+    fn enabled(&self, metadata: &Metadata<'_>, _: crate::subscribe::Context<'_, C>) -> bool {
+        self.current_level() >= *metadata.level()
+    }
+
+    fn max_level_hint(&self) -> Option<LevelFilter> {
+        // The thread-local override may raise the enabled level above
+        // `default` on any thread, so the only hint we can give is the
+        // most verbose level any thread could ever request.
+        Some(LevelFilter::TRACE)
+    }
+}
+
+#[cfg(feature = "registry")]
+impl<C> crate::filter::Filter<C> for PerThreadLevelFilter {
+    fn enabled(&self, metadata: &Metadata<'_>, _: &crate::subscribe::Context<'_, C>) -> bool {
+        self.current_level() >= *metadata.level()
+    }
+
+    fn callsite_enabled(&self, _metadata: &'static Metadata<'static>) -> Interest {
+        Interest::sometimes()
+    }
+
+    fn max_level_hint(&self) -> Option<LevelFilter> {
+        Some(LevelFilter::TRACE)
+    }
+}
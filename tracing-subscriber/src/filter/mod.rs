@@ -5,9 +5,50 @@
 #[cfg(feature = "env-filter")]
 mod env;
 mod level;
+mod targets;
+#[cfg(feature = "registry")]
+pub(crate) mod layer_filters;
+#[cfg(feature = "registry")]
+pub mod combinator;
+#[cfg(feature = "registry")]
+mod sampled;
+#[cfg(feature = "registry")]
+mod rate_limit;
+#[cfg(feature = "registry")]
+mod interest_pin;
+#[cfg(feature = "registry")]
+mod cooperative_budget;
+#[cfg(feature = "registry")]
+mod event_budget;
 
-pub use self::level::{LevelFilter, ParseError as LevelParseError};
+pub use self::level::{
+    set_thread_level, LevelFilter, ParseError as LevelParseError, PerThreadLevelFilter,
+    ThreadLevelGuard,
+};
+pub use self::targets::{ParseError as TargetsParseError, Targets};
 
 #[cfg(feature = "env-filter")]
 #[cfg_attr(docsrs, doc(cfg(feature = "env-filter")))]
 pub use self::env::*;
+
+#[cfg(feature = "registry")]
+#[cfg_attr(docsrs, doc(cfg(feature = "registry")))]
+pub use self::layer_filters::{Filter, Filtered};
+#[cfg(feature = "registry")]
+#[cfg_attr(docsrs, doc(cfg(feature = "registry")))]
+pub use self::combinator::FilterExt;
+#[cfg(feature = "registry")]
+#[cfg_attr(docsrs, doc(cfg(feature = "registry")))]
+pub use self::sampled::Sampled;
+#[cfg(feature = "registry")]
+#[cfg_attr(docsrs, doc(cfg(feature = "registry")))]
+pub use self::rate_limit::RateLimit;
+#[cfg(feature = "registry")]
+#[cfg_attr(docsrs, doc(cfg(feature = "registry")))]
+pub use self::interest_pin::InterestPin;
+#[cfg(feature = "registry")]
+#[cfg_attr(docsrs, doc(cfg(feature = "registry")))]
+pub use self::cooperative_budget::CooperativeBudget;
+#[cfg(feature = "registry")]
+#[cfg_attr(docsrs, doc(cfg(feature = "registry")))]
+pub use self::event_budget::EventBudget;
@@ -0,0 +1,115 @@
+//! A [`Filter`] that rate-limits events on a per-callsite basis.
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+use tracing_core::{callsite, collect::Collect, Metadata};
+
+use super::Filter;
+use crate::subscribe::Context;
+use crate::sync::RwLock;
+
+/// A [`Filter`] that enables at most `max_per_window` events per callsite
+/// within each rolling `window`, suppressing the rest.
+///
+/// This is useful for callsites that may emit a burst of identical events
+/// (for instance, a WARN logged on every iteration of a hot loop), which
+/// would otherwise drown out other, more useful diagnostics.
+///
+/// When a window rolls over, if any events were suppressed during it, a
+/// synthetic event is emitted (at the same target and level as this
+/// filter's own diagnostics) reporting how many similar events were
+/// dropped. This requires the `tracing` feature; without it, events are
+/// still suppressed, but no such notice is emitted.
+///
+/// `RateLimit` only limits events; it has no effect on spans, which are
+/// always enabled.
+#[derive(Debug)]
+pub struct RateLimit {
+    max_per_window: usize,
+    window: Duration,
+    state: RwLock<HashMap<callsite::Identifier, WindowState>>,
+}
+
+#[derive(Debug)]
+struct WindowState {
+    window_start: Instant,
+    count: usize,
+    suppressed: usize,
+}
+
+impl WindowState {
+    fn new(now: Instant) -> Self {
+        Self {
+            window_start: now,
+            count: 0,
+            suppressed: 0,
+        }
+    }
+}
+
+impl RateLimit {
+    /// Returns a new `RateLimit` that enables at most `max_per_window`
+    /// events per callsite within each `window`.
+    pub fn new(max_per_window: usize, window: Duration) -> Self {
+        Self {
+            max_per_window,
+            window,
+            state: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Decides whether the callsite identified by `id` is enabled, and
+    /// returns the number of events that were suppressed in the window that
+    /// just rolled over, if any.
+    fn check(&self, id: callsite::Identifier) -> (bool, usize) {
+        let now = Instant::now();
+        let mut state = try_lock!(self.state.write(), else return (true, 0));
+        let entry = state.entry(id).or_insert_with(|| WindowState::new(now));
+
+        if now.duration_since(entry.window_start) >= self.window {
+            let rolled_over_suppressed = entry.suppressed;
+            *entry = WindowState::new(now);
+            entry.count = 1;
+            return (true, rolled_over_suppressed);
+        }
+
+        if entry.count < self.max_per_window {
+            entry.count += 1;
+            (true, 0)
+        } else {
+            entry.suppressed += 1;
+            (false, 0)
+        }
+    }
+}
+
+impl<C> Filter<C> for RateLimit
+where
+    C: Collect,
+{
+    fn enabled(&self, meta: &Metadata<'_>, _cx: &Context<'_, C>) -> bool {
+        if meta.is_span() {
+            return true;
+        }
+
+        let (enabled, suppressed) = self.check(meta.callsite());
+
+        #[cfg(feature = "tracing")]
+        if suppressed > 0 {
+            tracing::warn!(
+                target: "tracing_subscriber::filter::rate_limit",
+                suppressed,
+                original.target = meta.target(),
+                original.name = meta.name(),
+                "suppressed {} similar events",
+                suppressed,
+            );
+        }
+        #[cfg(not(feature = "tracing"))]
+        let _ = suppressed;
+
+        enabled
+    }
+}
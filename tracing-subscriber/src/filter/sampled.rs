@@ -0,0 +1,218 @@
+//! A [`Filter`] implementing head-based sampling with per-span-tree
+//! consistency.
+use std::{
+    collections::hash_map::DefaultHasher,
+    fmt,
+    hash::{Hash, Hasher},
+};
+
+use tracing_core::{
+    collect::Collect,
+    field::{Field, Visit},
+    span, Metadata,
+};
+
+use super::Filter;
+use crate::registry::LookupSpan;
+use crate::subscribe::Context;
+
+/// A [`Filter`] that samples a fraction of root spans, and propagates that
+/// sampling decision to every descendant span and event of a sampled root,
+/// so a span tree is never partially sampled.
+///
+/// The sampling decision is made once, when a root span is created: it is
+/// seeded by the value of that span's `trace_id` field, if it records one,
+/// or by the span's [`Id`](span::Id) otherwise. Every other span in the
+/// tree inherits its root's decision, which is cached in that span's
+/// [extensions](crate::registry::Extensions) so it need only be looked up,
+/// not recomputed.
+///
+/// Because the decision lives on each span, a `Sampled` filter only
+/// affects events recorded inside some tracked span; events recorded
+/// outside of any span (or before the current span was observed by this
+/// filter) are always enabled, since there is no tree for them to belong
+/// to.
+#[derive(Clone, Debug)]
+pub struct Sampled {
+    ratio: f64,
+}
+
+/// The sampling decision made for the root of a span tree, cached in that
+/// span's extensions and inherited by its descendants.
+#[derive(Clone, Copy, Debug)]
+struct SampleDecision(bool);
+
+impl Sampled {
+    /// Returns a new `Sampled` filter that keeps approximately `ratio` of
+    /// root span trees.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `ratio` is not between `0.0` and `1.0`.
+    pub fn new(ratio: f64) -> Self {
+        assert!(
+            (0.0..=1.0).contains(&ratio),
+            "sampling ratio must be between 0.0 and 1.0, got {}",
+            ratio
+        );
+        Self { ratio }
+    }
+
+    fn sample(&self, seed: u64) -> SampleDecision {
+        // Map the seed into [0, 1) and compare it to the configured ratio,
+        // so that the same seed always yields the same decision.
+        let fraction = (seed as f64) / (u64::MAX as f64);
+        SampleDecision(fraction < self.ratio)
+    }
+}
+
+/// Mixes `id` across the full `u64` range.
+///
+/// Span ids are small, densely-packed sequential integers, so using one
+/// directly as a sampling seed would concentrate every seed near zero and
+/// defeat [`Sampled::sample`]'s `fraction < self.ratio` comparison
+/// (`fraction` would be ~0 for any realistic id, regardless of `ratio`).
+fn hash_span_id(id: u64) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    id.hash(&mut hasher);
+    hasher.finish()
+}
+
+impl<C> Filter<C> for Sampled
+where
+    C: Collect + for<'span> LookupSpan<'span>,
+{
+    fn enabled(&self, _meta: &Metadata<'_>, cx: &Context<'_, C>) -> bool {
+        match cx.lookup_current() {
+            Some(span) => span
+                .extensions()
+                .get::<SampleDecision>()
+                .map(|decision| decision.0)
+                .unwrap_or(true),
+            None => true,
+        }
+    }
+
+    fn on_new_span(&self, attrs: &span::Attributes<'_>, id: &span::Id, cx: Context<'_, C>) {
+        let parent = if attrs.is_root() {
+            None
+        } else if let Some(parent) = attrs.parent() {
+            Some(parent.clone())
+        } else {
+            cx.current_span().id().cloned()
+        };
+
+        let decision = match parent.and_then(|parent| cx.span(&parent)) {
+            Some(parent) => parent
+                .extensions()
+                .get::<SampleDecision>()
+                .copied()
+                .unwrap_or_else(|| self.sample(hash_span_id(id.into_u64()))),
+            None => {
+                let mut visitor = TraceIdVisitor::default();
+                attrs.record(&mut visitor);
+                self.sample(
+                    visitor
+                        .seed()
+                        .unwrap_or_else(|| hash_span_id(id.into_u64())),
+                )
+            }
+        };
+
+        if let Some(span) = cx.span(id) {
+            span.extensions_mut().insert(decision);
+        }
+    }
+}
+
+/// Extracts a sampling seed from a span's `trace_id` field, if it has one.
+#[derive(Default)]
+struct TraceIdVisitor {
+    seed: Option<u64>,
+}
+
+impl TraceIdVisitor {
+    fn seed(&self) -> Option<u64> {
+        self.seed
+    }
+
+    fn record(&mut self, field: &Field, value: impl Hash) {
+        if field.name() == "trace_id" {
+            let mut hasher = DefaultHasher::new();
+            value.hash(&mut hasher);
+            self.seed = Some(hasher.finish());
+        }
+    }
+}
+
+impl Visit for TraceIdVisitor {
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.record(field, value);
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.record(field, value);
+    }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.record(field, value);
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.record(field, value);
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.record(field, format!("{:?}", value));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+    use std::sync::{atomic::AtomicUsize, atomic::Ordering, Arc};
+
+    #[derive(Clone, Default)]
+    struct CountEvents(Arc<AtomicUsize>);
+
+    impl<C> crate::subscribe::Subscribe<C> for CountEvents
+    where
+        C: Collect + for<'span> LookupSpan<'span>,
+    {
+        fn on_event(&self, _event: &tracing_core::Event<'_>, _cx: Context<'_, C>) {
+            self.0.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    #[test]
+    fn root_spans_without_trace_id_sample_at_roughly_the_configured_ratio() {
+        // Every root span here relies on the `id.into_u64()` fallback seed
+        // (no `trace_id` field is set), which is what used to be used
+        // un-hashed and therefore concentrated near zero, sampling nearly
+        // every span regardless of `ratio`.
+        const ROOTS: usize = 2000;
+        const RATIO: f64 = 0.25;
+
+        let seen = Arc::new(AtomicUsize::new(0));
+        let filter = Sampled::new(RATIO);
+        let subscriber =
+            crate::registry().with(CountEvents(seen.clone()).with_filter(filter));
+
+        tracing_core::dispatch::with_default(&tracing_core::Dispatch::new(subscriber), || {
+            for _ in 0..ROOTS {
+                let span = tracing::info_span!("root");
+                let _guard = span.enter();
+                tracing::info!("event");
+            }
+        });
+
+        let observed = seen.load(Ordering::Relaxed) as f64 / ROOTS as f64;
+        assert!(
+            (observed - RATIO).abs() < 0.05,
+            "observed sample rate {} was not within tolerance of configured ratio {}",
+            observed,
+            RATIO,
+        );
+    }
+}
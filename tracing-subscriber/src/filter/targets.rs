@@ -0,0 +1,166 @@
+//! A simple target/level filter, without the dynamic directive syntax
+//! supported by [`EnvFilter`](super::EnvFilter).
+use std::iter::FromIterator;
+use std::str::FromStr;
+
+use tracing_core::{Collect, Interest, LevelFilter, Metadata};
+
+use crate::subscribe::{Context, Subscribe};
+
+/// A filter that enables spans and events whose [target] starts with one of
+/// a fixed set of prefixes, at or below a configured [`LevelFilter`].
+///
+/// Unlike [`EnvFilter`](super::EnvFilter), `Targets` does not support span
+/// field matching or parsing `RUST_LOG`-style directive syntax at runtime.
+/// Its rules are a small, fixed list of `(target prefix, level)` pairs
+/// checked by a linear scan, with no locking, parsing, or heap allocation
+/// once constructed — a cheaper default for the common case of "this
+/// subtree of targets gets this level".
+///
+/// [target]: tracing_core::Metadata::target
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Targets {
+    targets: Vec<(String, LevelFilter)>,
+    default: LevelFilter,
+}
+
+impl Default for Targets {
+    fn default() -> Self {
+        Self {
+            targets: Vec::new(),
+            default: LevelFilter::OFF,
+        }
+    }
+}
+
+impl Targets {
+    /// Returns a new `Targets` filter that enables nothing by default.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the level enabled for spans and events whose target starts with
+    /// `target`. A given event or span is matched against the
+    /// *longest matching prefix* that was configured.
+    pub fn with_target(mut self, target: impl Into<String>, level: impl Into<LevelFilter>) -> Self {
+        self.targets.push((target.into(), level.into()));
+        self
+    }
+
+    /// Sets the level enabled for targets that don't match any configured
+    /// prefix. Defaults to [`LevelFilter::OFF`].
+    pub fn with_default(mut self, level: impl Into<LevelFilter>) -> Self {
+        self.default = level.into();
+        self
+    }
+
+    fn level_for(&self, target: &str) -> LevelFilter {
+        self.targets
+            .iter()
+            .filter(|(prefix, _)| target.starts_with(prefix.as_str()))
+            .max_by_key(|(prefix, _)| prefix.len())
+            .map(|(_, level)| *level)
+            .unwrap_or(self.default)
+    }
+
+    fn enabled_inner(&self, metadata: &Metadata<'_>) -> bool {
+        self.level_for(metadata.target()) >= *metadata.level()
+    }
+
+    fn max_level_hint_inner(&self) -> Option<LevelFilter> {
+        let max = self
+            .targets
+            .iter()
+            .map(|(_, level)| *level)
+            .fold(self.default, std::cmp::max);
+        if max == LevelFilter::OFF && self.targets.is_empty() {
+            None
+        } else {
+            Some(max)
+        }
+    }
+}
+
+impl<I, T> FromIterator<(T, I)> for Targets
+where
+    T: Into<String>,
+    I: Into<LevelFilter>,
+{
+    fn from_iter<Iter: IntoIterator<Item = (T, I)>>(iter: Iter) -> Self {
+        let mut this = Self::new();
+        for (target, level) in iter {
+            this = this.with_target(target, level);
+        }
+        this
+    }
+}
+
+/// Errors returned when parsing a [`Targets`] filter from a `target=level`
+/// directive string, such as `"my_crate=debug,my_crate::noisy=warn"`.
+#[derive(Debug)]
+pub struct ParseError(String);
+
+impl std::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "invalid `Targets` directive: {}", self.0)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+impl FromStr for Targets {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut this = Self::new();
+        for directive in s.split(',').map(str::trim).filter(|s| !s.is_empty()) {
+            let mut parts = directive.splitn(2, '=');
+            let target = parts.next().unwrap_or_default();
+            let level = parts
+                .next()
+                .ok_or_else(|| ParseError(directive.to_string()))?;
+            let level = level
+                .parse::<LevelFilter>()
+                .map_err(|_| ParseError(directive.to_string()))?;
+            this = this.with_target(target, level);
+        }
+        Ok(this)
+    }
+}
+
+impl<C: Collect> Subscribe<C> for Targets {
+    fn register_callsite(&self, metadata: &'static Metadata<'static>) -> Interest {
+        if self.enabled_inner(metadata) {
+            Interest::always()
+        } else {
+            Interest::never()
+        }
+    }
+
+    fn enabled(&self, metadata: &Metadata<'_>, _: Context<'_, C>) -> bool {
+        self.enabled_inner(metadata)
+    }
+
+    fn max_level_hint(&self) -> Option<LevelFilter> {
+        self.max_level_hint_inner()
+    }
+}
+
+#[cfg(feature = "registry")]
+impl<C> crate::filter::Filter<C> for Targets {
+    fn enabled(&self, metadata: &Metadata<'_>, _: &Context<'_, C>) -> bool {
+        self.enabled_inner(metadata)
+    }
+
+    fn callsite_enabled(&self, metadata: &'static Metadata<'static>) -> Interest {
+        if self.enabled_inner(metadata) {
+            Interest::always()
+        } else {
+            Interest::never()
+        }
+    }
+
+    fn max_level_hint(&self) -> Option<LevelFilter> {
+        self.max_level_hint_inner()
+    }
+}
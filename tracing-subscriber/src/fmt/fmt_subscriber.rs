@@ -1,6 +1,6 @@
 use crate::{
     field::RecordFields,
-    fmt::{format, FormatEvent, FormatFields, MakeWriter, TestWriter},
+    fmt::{format, writer::BoxMakeWriter, FormatEvent, FormatFields, MakeWriter, TestWriter},
     registry::{LookupSpan, SpanRef},
     subscribe::{self, Context},
 };
@@ -63,6 +63,10 @@ use tracing_core::{
 #[derive(Debug)]
 pub struct Subscriber<C, N = format::DefaultFields, E = format::Format, W = fn() -> io::Stdout> {
     make_writer: W,
+    // If set, span lifecycle events (NEW/ENTER/EXIT/CLOSE) are written here
+    // instead of through `make_writer`, so high-volume lifecycle output
+    // doesn't have to share a destination with ordinary events.
+    span_writer: Option<BoxMakeWriter>,
     fmt_fields: N,
     fmt_event: E,
     fmt_span: format::FmtSpanConfig,
@@ -113,6 +117,7 @@ where
             fmt_event: e,
             fmt_span: self.fmt_span,
             make_writer: self.make_writer,
+            span_writer: self.span_writer,
             _inner: self._inner,
         }
     }
@@ -148,6 +153,7 @@ impl<C, N, E, W> Subscriber<C, N, E, W> {
             fmt_event: self.fmt_event,
             fmt_span: self.fmt_span,
             make_writer,
+            span_writer: self.span_writer,
             _inner: self._inner,
         }
     }
@@ -180,9 +186,31 @@ impl<C, N, E, W> Subscriber<C, N, E, W> {
             fmt_event: self.fmt_event,
             fmt_span: self.fmt_span,
             make_writer: TestWriter::default(),
+            span_writer: self.span_writer,
             _inner: self._inner,
         }
     }
+
+    /// Sets the [`MakeWriter`] that the [`Subscriber`] being built will use
+    /// to write synthesized span lifecycle events (NEW/ENTER/EXIT/CLOSE),
+    /// separately from ordinary events.
+    ///
+    /// Lifecycle event volume is often much higher than ordinary event
+    /// volume, so routing it to its own writer (e.g. a dedicated file) keeps
+    /// it from crowding out human-facing logs written via
+    /// [`with_writer`](Subscriber::with_writer).
+    ///
+    /// This has no effect unless [`with_span_events`](Subscriber::with_span_events)
+    /// is also used to enable lifecycle events in the first place.
+    pub fn with_span_writer<W2>(self, make_writer: W2) -> Self
+    where
+        W2: for<'writer> MakeWriter<'writer> + Send + Sync + 'static,
+    {
+        Subscriber {
+            span_writer: Some(BoxMakeWriter::new(make_writer)),
+            ..self
+        }
+    }
 }
 
 impl<C, N, L, T, W> Subscriber<C, N, format::Format<L, T>, W>
@@ -206,6 +234,7 @@ where
             fmt_fields: self.fmt_fields,
             fmt_span: self.fmt_span,
             make_writer: self.make_writer,
+            span_writer: self.span_writer,
             _inner: self._inner,
         }
     }
@@ -217,6 +246,7 @@ where
             fmt_fields: self.fmt_fields,
             fmt_span: self.fmt_span.without_time(),
             make_writer: self.make_writer,
+            span_writer: self.span_writer,
             _inner: self._inner,
         }
     }
@@ -333,6 +363,28 @@ where
             fmt_fields: self.fmt_fields,
             fmt_span: self.fmt_span,
             make_writer: self.make_writer,
+            span_writer: self.span_writer,
+            _inner: self._inner,
+        }
+    }
+
+    /// Sets the subscriber being built to format events using a user-supplied
+    /// [template string](format::Template), instead of a fixed layout.
+    ///
+    /// See [`format::Template`] for the supported placeholders.
+    pub fn with_template(
+        self,
+        template: impl AsRef<str>,
+    ) -> Subscriber<C, N, format::Format<format::Template, T>, W>
+    where
+        N: for<'writer> FormatFields<'writer> + 'static,
+    {
+        Subscriber {
+            fmt_event: self.fmt_event.with_template(template),
+            fmt_fields: self.fmt_fields,
+            fmt_span: self.fmt_span,
+            make_writer: self.make_writer,
+            span_writer: self.span_writer,
             _inner: self._inner,
         }
     }
@@ -346,6 +398,7 @@ where
             fmt_fields: format::Pretty::default(),
             fmt_span: self.fmt_span,
             make_writer: self.make_writer,
+            span_writer: self.span_writer,
             _inner: self._inner,
         }
     }
@@ -373,6 +426,7 @@ where
             fmt_fields: format::JsonFields::new(),
             fmt_span: self.fmt_span,
             make_writer: self.make_writer,
+            span_writer: self.span_writer,
             _inner: self._inner,
         }
     }
@@ -424,6 +478,59 @@ impl<C, T, W> Subscriber<C, format::JsonFields, format::Format<format::Json, T>,
             ..self
         }
     }
+
+    /// Sets whether or not the formatter will stringify `i64`/`u64` event
+    /// field values that can't be represented exactly as a double-precision
+    /// float.
+    ///
+    /// See [`format::Json::with_stringified_big_ints`].
+    pub fn with_stringified_big_ints(
+        self,
+        stringify_big_ints: bool,
+    ) -> Subscriber<C, format::JsonFields, format::Format<format::Json, T>, W> {
+        Subscriber {
+            fmt_event: self.fmt_event.with_stringified_big_ints(stringify_big_ints),
+            fmt_fields: format::JsonFields::new(),
+            ..self
+        }
+    }
+
+    /// Sets whether or not fields recorded on a span are inherited as
+    /// default values by events recorded inside that span (and its
+    /// descendants).
+    ///
+    /// See [`format::Json::with_inherited_field_defaults`].
+    pub fn with_inherited_field_defaults(
+        self,
+        inherit_field_defaults: bool,
+    ) -> Subscriber<C, format::JsonFields, format::Format<format::Json, T>, W> {
+        Subscriber {
+            fmt_event: self
+                .fmt_event
+                .with_inherited_field_defaults(inherit_field_defaults),
+            fmt_fields: format::JsonFields::new(),
+            ..self
+        }
+    }
+
+    /// Sets whether or not the formatter will include `trace_id` and
+    /// `span_id` fields taken from the current span's [`TraceContext`].
+    ///
+    /// See [`format::Json::with_trace_context`].
+    ///
+    /// [`TraceContext`]: crate::registry::trace_context::TraceContext
+    #[cfg(feature = "trace-context")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "trace-context")))]
+    pub fn with_trace_context(
+        self,
+        display_trace_context: bool,
+    ) -> Subscriber<C, format::JsonFields, format::Format<format::Json, T>, W> {
+        Subscriber {
+            fmt_event: self.fmt_event.with_trace_context(display_trace_context),
+            fmt_fields: format::JsonFields::new(),
+            ..self
+        }
+    }
 }
 
 impl<C, N, E, W> Subscriber<C, N, E, W> {
@@ -438,6 +545,7 @@ impl<C, N, E, W> Subscriber<C, N, E, W> {
             fmt_fields,
             fmt_span: self.fmt_span,
             make_writer: self.make_writer,
+            span_writer: self.span_writer,
             _inner: self._inner,
         }
     }
@@ -450,6 +558,7 @@ impl<C> Default for Subscriber<C> {
             fmt_event: format::Format::default(),
             fmt_span: format::FmtSpanConfig::default(),
             make_writer: io::stdout,
+            span_writer: None,
             _inner: PhantomData,
         }
     }
@@ -469,6 +578,48 @@ where
             fmt_fields: &self.fmt_fields,
         }
     }
+
+    fn write_formatted(&self, ctx: Context<'_, C>, event: &Event<'_>, mut writer: impl io::Write) {
+        thread_local! {
+            static BUF: RefCell<String> = RefCell::new(String::new());
+        }
+
+        BUF.with(|buf| {
+            let borrow = buf.try_borrow_mut();
+            let mut a;
+            let mut b;
+            let mut buf = match borrow {
+                Ok(buf) => {
+                    a = buf;
+                    &mut *a
+                }
+                _ => {
+                    b = String::new();
+                    &mut b
+                }
+            };
+
+            let ctx = self.make_ctx(ctx);
+            if self.fmt_event.format_event(&ctx, &mut buf, event).is_ok() {
+                let _ = io::Write::write_all(&mut writer, buf.as_bytes());
+            }
+
+            buf.clear();
+        });
+    }
+
+    /// Emits a synthesized span lifecycle event, routing it to the
+    /// [`span_writer`](Subscriber::with_span_writer) if one is configured,
+    /// and falling back to the ordinary event writer otherwise.
+    fn emit_lifecycle_event(&self, event: &Event<'_>, ctx: Context<'_, C>) {
+        match &self.span_writer {
+            Some(span_writer) => {
+                let writer = span_writer.make_writer_for(event.metadata());
+                self.write_formatted(ctx, event, writer);
+            }
+            None => subscribe::Subscribe::on_event(self, event, ctx),
+        }
+    }
 }
 
 /// A formatted representation of a span's fields stored in its [extensions].
@@ -569,7 +720,7 @@ where
             with_event_from_span!(id, span, "message" = "new", |event| {
                 drop(extensions);
                 drop(span);
-                self.on_event(&event, ctx);
+                self.emit_lifecycle_event(&event, ctx);
             });
         }
     }
@@ -607,7 +758,7 @@ where
                 with_event_from_span!(id, span, "message" = "enter", |event| {
                     drop(extensions);
                     drop(span);
-                    self.on_event(&event, ctx);
+                    self.emit_lifecycle_event(&event, ctx);
                 });
             }
         }
@@ -627,7 +778,7 @@ where
                 with_event_from_span!(id, span, "message" = "exit", |event| {
                     drop(extensions);
                     drop(span);
-                    self.on_event(&event, ctx);
+                    self.emit_lifecycle_event(&event, ctx);
                 });
             }
         }
@@ -657,47 +808,22 @@ where
                     |event| {
                         drop(extensions);
                         drop(span);
-                        self.on_event(&event, ctx);
+                        self.emit_lifecycle_event(&event, ctx);
                     }
                 );
             } else {
                 with_event_from_span!(id, span, "message" = "close", |event| {
                     drop(extensions);
                     drop(span);
-                    self.on_event(&event, ctx);
+                    self.emit_lifecycle_event(&event, ctx);
                 });
             }
         }
     }
 
     fn on_event(&self, event: &Event<'_>, ctx: Context<'_, C>) {
-        thread_local! {
-            static BUF: RefCell<String> = RefCell::new(String::new());
-        }
-
-        BUF.with(|buf| {
-            let borrow = buf.try_borrow_mut();
-            let mut a;
-            let mut b;
-            let mut buf = match borrow {
-                Ok(buf) => {
-                    a = buf;
-                    &mut *a
-                }
-                _ => {
-                    b = String::new();
-                    &mut b
-                }
-            };
-
-            let ctx = self.make_ctx(ctx);
-            if self.fmt_event.format_event(&ctx, &mut buf, event).is_ok() {
-                let mut writer = self.make_writer.make_writer_for(event.metadata());
-                let _ = io::Write::write_all(&mut writer, buf.as_bytes());
-            }
-
-            buf.clear();
-        });
+        let writer = self.make_writer.make_writer_for(event.metadata());
+        self.write_formatted(ctx, event, writer);
     }
 
     unsafe fn downcast_raw(&self, id: TypeId) -> Option<NonNull<()>> {
@@ -757,8 +883,10 @@ where
     {
         // visit all the current spans
         if let Some(leaf) = self.ctx.lookup_current() {
-            for span in leaf.scope().from_root() {
-                f(&span)?;
+            if let Some(scope) = self.ctx.span_scope(&leaf.id()) {
+                for span in scope.from_root() {
+                    f(&span)?;
+                }
             }
         }
         Ok(())
@@ -55,6 +55,10 @@ pub struct Json {
     pub(crate) flatten_event: bool,
     pub(crate) display_current_span: bool,
     pub(crate) display_span_list: bool,
+    pub(crate) stringify_big_ints: bool,
+    pub(crate) inherit_field_defaults: bool,
+    #[cfg(feature = "trace-context")]
+    pub(crate) display_trace_context: bool,
 }
 
 impl Json {
@@ -73,6 +77,58 @@ impl Json {
     pub fn with_span_list(&mut self, display_span_list: bool) {
         self.display_span_list = display_span_list;
     }
+
+    /// If set to `true`, event field values that are `i64`/`u64` integers
+    /// whose magnitude exceeds 2^53 (the largest integer a double-precision
+    /// float, and therefore a JavaScript `Number`, can represent exactly)
+    /// are emitted as JSON strings rather than numbers, to avoid silent
+    /// precision loss in JavaScript-based log backends.
+    ///
+    /// This is independent of [`JsonFields::with_stringified_big_ints`],
+    /// which controls the same behavior for fields recorded on spans.
+    pub fn with_stringified_big_ints(&mut self, stringify_big_ints: bool) {
+        self.stringify_big_ints = stringify_big_ints;
+    }
+
+    /// If set to `true`, fields recorded on a span (e.g. `component = "db"`)
+    /// are treated as defaults for every event recorded inside that span
+    /// (and any of its descendant spans), and are merged into the `"fields"`
+    /// object of each such event, so that callsites don't need to repeat
+    /// them. A field recorded directly on the event, or on a nearer
+    /// ancestor span, always takes precedence over one inherited from a
+    /// farther ancestor.
+    ///
+    /// This does not affect the `"span"`/`"spans"` objects (see
+    /// [`Json::with_current_span`] and [`Json::with_span_list`]), which
+    /// already show each span's own fields; it only controls whether those
+    /// fields are additionally copied into events. It has no effect when
+    /// [`Json::flatten_event`] is set, since flattened events are written
+    /// directly to the root object as they're visited, rather than
+    /// collected into a map first.
+    pub fn with_inherited_field_defaults(&mut self, inherit_field_defaults: bool) {
+        self.inherit_field_defaults = inherit_field_defaults;
+    }
+
+    /// If set to `true`, formatted events include top-level `trace_id` and
+    /// `span_id` fields -- hex-encoded at the same widths OpenTelemetry uses
+    /// (32 and 16 hex digits, respectively) -- taken from the current span's
+    /// [`TraceContext`], so logs can be correlated with traces in a backend
+    /// that understands OpenTelemetry-style ids without a custom pipeline.
+    ///
+    /// This has no effect unless a [`TraceContextLayer`] elsewhere in the
+    /// subscriber stack has already assigned the current span a trace
+    /// context; if none is found, no `trace_id`/`span_id` fields are added
+    /// to that event.
+    ///
+    /// This option requires the "trace-context" feature flag.
+    ///
+    /// [`TraceContext`]: crate::registry::trace_context::TraceContext
+    /// [`TraceContextLayer`]: crate::registry::trace_context::TraceContextLayer
+    #[cfg(feature = "trace-context")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "trace-context")))]
+    pub fn with_trace_context(&mut self, display_trace_context: bool) {
+        self.display_trace_context = display_trace_context;
+    }
 }
 
 struct SerializableContext<'a, 'b, Span, N>(
@@ -96,8 +152,15 @@ where
         let mut serializer = serializer_o.serialize_seq(None)?;
 
         if let Some(leaf_span) = self.0.lookup_current() {
-            for span in leaf_span.scope().from_root() {
-                serializer.serialize_element(&SerializableSpan(&span, self.1))?;
+            // Use the context's (possibly `Filtered`-scoped) span iteration,
+            // rather than `leaf_span.scope()` directly, so that a `Json`
+            // subscriber wrapped in `Filtered` only lists spans its own
+            // filter enabled, even if another subscriber in the same stack
+            // saw a different set of spans for this trace.
+            if let Some(scope) = self.0.span_scope(&leaf_span.id()) {
+                for span in scope.from_root() {
+                    serializer.serialize_element(&SerializableSpan(&span, self.1))?;
+                }
             }
         }
 
@@ -169,7 +232,7 @@ where
             // that the fields are not supposed to be missing.
             Err(e) => serializer.serialize_entry("field_error", &format!("{}", e))?,
         };
-        serializer.serialize_entry("name", self.0.metadata().name())?;
+        serializer.serialize_entry("name", &self.0.display_name())?;
         serializer.end()
     }
 }
@@ -212,23 +275,70 @@ where
                 serializer.serialize_entry("level", &meta.level().as_serde())?;
             }
 
+            #[cfg(feature = "trace-context")]
+            if self.format.display_trace_context {
+                let trace_context = ctx
+                    .ctx
+                    .event_span(event)
+                    .and_then(|span| crate::registry::trace_context::TraceContext::current(&ctx.ctx, &span.id()));
+                if let Some(trace_context) = trace_context {
+                    serializer.serialize_entry("trace_id", &trace_context.trace_id.to_string())?;
+                    serializer.serialize_entry("span_id", &trace_context.span_id.to_string())?;
+                }
+            }
+
             let format_field_marker: std::marker::PhantomData<N> = std::marker::PhantomData;
 
             let current_span = if self.format.display_current_span || self.format.display_span_list
             {
-                event
-                    .parent()
-                    .and_then(|id| ctx.span(id))
-                    .or_else(|| ctx.lookup_current())
+                // Walk from the event's span outward until we find one this
+                // `Json` subscriber's own filter (if wrapped in `Filtered`)
+                // actually enabled, rather than assuming the raw parent span
+                // was seen by us -- another subscriber in the stack may have
+                // enabled it while this one didn't.
+                ctx.ctx
+                    .event_scope(event)
+                    .and_then(|mut scope| scope.next())
             } else {
                 None
             };
 
             if self.format.flatten_event {
-                let mut visitor = tracing_serde::SerdeMapVisitor::new(serializer);
+                if self.format.stringify_big_ints {
+                    let mut visitor = BigIntSafeMapVisitor::new(serializer);
+                    event.record(&mut visitor);
+                    serializer = visitor.take_serializer()?;
+                } else {
+                    let mut visitor = tracing_serde::SerdeMapVisitor::new(serializer);
+                    event.record(&mut visitor);
+                    serializer = visitor.take_serializer()?;
+                }
+            } else if self.format.inherit_field_defaults {
+                let mut visitor = OwnedMapVisitor::new(self.format.stringify_big_ints);
                 event.record(&mut visitor);
+                let mut values = visitor.values;
+
+                if let Some(scope) = ctx.ctx.event_scope(event) {
+                    for span in scope {
+                        let ext = span.extensions();
+                        if let Some(data) = ext.get::<FormattedFields<N>>() {
+                            if let Ok(serde_json::Value::Object(fields)) =
+                                serde_json::from_str::<serde_json::Value>(data)
+                            {
+                                for (name, value) in fields {
+                                    values.entry(name).or_insert(value);
+                                }
+                            }
+                        }
+                    }
+                }
 
-                serializer = visitor.take_serializer()?;
+                serializer.serialize_entry("fields", &values)?;
+            } else if self.format.stringify_big_ints {
+                serializer.serialize_entry(
+                    "fields",
+                    &SerializableBigIntSafeFields(event),
+                )?;
             } else {
                 use tracing_serde::fields::AsMap;
                 serializer.serialize_entry("fields", &event.field_map())?;
@@ -281,12 +391,184 @@ where
     }
 }
 
+/// The largest integer magnitude a double-precision float (and therefore a
+/// JavaScript `Number`) can represent without loss of precision.
+const MAX_SAFE_INT_MAGNITUDE: i128 = 1i128 << 53;
+
+/// Serializes an entry, stringifying `i64`/`u64` values whose magnitude
+/// exceeds [`MAX_SAFE_INT_MAGNITUDE`] rather than serializing them as
+/// numbers. Used by [`BigIntSafeMapVisitor`] and
+/// [`SerializableBigIntSafeFields`] to apply the same rule consistently to
+/// event fields that [`JsonVisitor`] already applies to span fields.
+fn serialize_int_entry<S: SerializeMap>(
+    serializer: &mut S,
+    name: &str,
+    magnitude: i128,
+    value: impl serde::Serialize,
+) -> Result<(), S::Error> {
+    if magnitude.abs() > MAX_SAFE_INT_MAGNITUDE {
+        serializer.serialize_entry(name, &magnitude.to_string())
+    } else {
+        serializer.serialize_entry(name, &value)
+    }
+}
+
+/// Implements [`field::Visit`] for some [`SerializeMap`], applying the same
+/// large-integer-as-string rule as [`JsonVisitor`].
+struct BigIntSafeMapVisitor<S: SerializeMap> {
+    serializer: S,
+    state: Result<(), S::Error>,
+}
+
+impl<S: SerializeMap> BigIntSafeMapVisitor<S> {
+    fn new(serializer: S) -> Self {
+        Self {
+            serializer,
+            state: Ok(()),
+        }
+    }
+
+    fn take_serializer(self) -> Result<S, S::Error> {
+        self.state?;
+        Ok(self.serializer)
+    }
+}
+
+impl<S: SerializeMap> field::Visit for BigIntSafeMapVisitor<S> {
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        if self.state.is_ok() {
+            self.state = self.serializer.serialize_entry(field.name(), &value);
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        if self.state.is_ok() {
+            self.state = self
+                .serializer
+                .serialize_entry(field.name(), &format_args!("{:?}", value));
+        }
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        if self.state.is_ok() {
+            self.state =
+                serialize_int_entry(&mut self.serializer, field.name(), value as i128, value);
+        }
+    }
+
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        if self.state.is_ok() {
+            self.state =
+                serialize_int_entry(&mut self.serializer, field.name(), value as i128, value);
+        }
+    }
+
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        if self.state.is_ok() {
+            self.state = self.serializer.serialize_entry(field.name(), &value);
+        }
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if self.state.is_ok() {
+            self.state = self.serializer.serialize_entry(field.name(), &value);
+        }
+    }
+}
+
+/// Serializes an [`Event`]'s fields as a map, applying the same
+/// large-integer-as-string rule as [`JsonVisitor`]. Used for the `"fields"`
+/// entry when [`Json::with_stringified_big_ints`] is enabled and events
+/// aren't flattened into the root object.
+struct SerializableBigIntSafeFields<'a, 'event>(&'a Event<'event>);
+
+impl<'a, 'event> serde::Serialize for SerializableBigIntSafeFields<'a, 'event> {
+    fn serialize<Ser>(&self, serializer: Ser) -> Result<Ser::Ok, Ser::Error>
+    where
+        Ser: serde::ser::Serializer,
+    {
+        let len = self.0.fields().count();
+        let serializer = serializer.serialize_map(Some(len))?;
+        let mut visitor = BigIntSafeMapVisitor::new(serializer);
+        self.0.record(&mut visitor);
+        visitor.take_serializer()?.end()
+    }
+}
+
+/// Records an [`Event`]'s fields into an owned map, applying the same
+/// large-integer-as-string rule as [`JsonVisitor`] when `stringify_big_ints`
+/// is set. Used by [`Json::with_inherited_field_defaults`] so that a span's
+/// default field values (read back from its already-formatted
+/// [`FormattedFields`]) can be merged in for any keys the event didn't
+/// record itself, before the combined map is serialized.
+struct OwnedMapVisitor {
+    values: BTreeMap<String, serde_json::Value>,
+    stringify_big_ints: bool,
+}
+
+impl OwnedMapVisitor {
+    fn new(stringify_big_ints: bool) -> Self {
+        Self {
+            values: BTreeMap::new(),
+            stringify_big_ints,
+        }
+    }
+}
+
+impl field::Visit for OwnedMapVisitor {
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.values
+            .insert(field.name().to_string(), serde_json::Value::from(value));
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.values.insert(
+            field.name().to_string(),
+            serde_json::Value::from(format!("{:?}", value)),
+        );
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        let json_value = if self.stringify_big_ints && value as u128 > MAX_SAFE_INT_MAGNITUDE as u128
+        {
+            serde_json::Value::from(value.to_string())
+        } else {
+            serde_json::Value::from(value)
+        };
+        self.values.insert(field.name().to_string(), json_value);
+    }
+
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        let json_value = if self.stringify_big_ints && (value as i128).abs() > MAX_SAFE_INT_MAGNITUDE
+        {
+            serde_json::Value::from(value.to_string())
+        } else {
+            serde_json::Value::from(value)
+        };
+        self.values.insert(field.name().to_string(), json_value);
+    }
+
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        self.values
+            .insert(field.name().to_string(), serde_json::Value::from(value));
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.values
+            .insert(field.name().to_string(), serde_json::Value::from(value));
+    }
+}
+
 impl Default for Json {
     fn default() -> Json {
         Json {
             flatten_event: false,
             display_current_span: true,
             display_span_list: true,
+            stringify_big_ints: false,
+            inherit_field_defaults: false,
+            #[cfg(feature = "trace-context")]
+            display_trace_context: false,
         }
     }
 }
@@ -295,16 +577,28 @@ impl Default for Json {
 ///
 #[derive(Debug)]
 pub struct JsonFields {
-    // reserve the ability to add fields to this without causing a breaking
-    // change in the future.
-    _private: (),
+    stringify_big_ints: bool,
 }
 
 impl JsonFields {
     /// Returns a new JSON [`FormatFields`] implementation.
     ///
     pub fn new() -> Self {
-        Self { _private: () }
+        Self {
+            stringify_big_ints: false,
+        }
+    }
+
+    /// If set to `true`, `i64`/`u64` field values whose magnitude exceeds
+    /// 2^53 (the largest integer a double-precision float, and therefore a
+    /// JavaScript `Number`, can represent exactly) are emitted as JSON
+    /// strings rather than numbers, to avoid silent precision loss in
+    /// JavaScript-based log backends.
+    pub fn with_stringified_big_ints(self, stringify_big_ints: bool) -> Self {
+        Self {
+            stringify_big_ints,
+            ..self
+        }
     }
 }
 
@@ -321,7 +615,7 @@ impl<'a> FormatFields<'a> for JsonFields {
         writer: &'a mut dyn fmt::Write,
         fields: R,
     ) -> fmt::Result {
-        let mut v = JsonVisitor::new(writer);
+        let mut v = JsonVisitor::new(writer).with_stringified_big_ints(self.stringify_big_ints);
         fields.record(&mut v);
         v.finish()
     }
@@ -350,7 +644,7 @@ impl<'a> FormatFields<'a> for JsonFields {
             let mut new = String::new();
             let map: BTreeMap<&'_ str, serde_json::Value> =
                 serde_json::from_str(current).map_err(|_| fmt::Error)?;
-            let mut v = JsonVisitor::new(&mut new);
+            let mut v = JsonVisitor::new(&mut new).with_stringified_big_ints(self.stringify_big_ints);
             v.values = map;
             fields.record(&mut v);
             v.finish()?;
@@ -358,7 +652,7 @@ impl<'a> FormatFields<'a> for JsonFields {
         } else {
             // If there are no previously recorded fields, we can just reuse the
             // existing string.
-            let mut v = JsonVisitor::new(current);
+            let mut v = JsonVisitor::new(current).with_stringified_big_ints(self.stringify_big_ints);
             fields.record(&mut v);
             v.finish()?;
         }
@@ -374,6 +668,7 @@ impl<'a> FormatFields<'a> for JsonFields {
 pub struct JsonVisitor<'a> {
     values: BTreeMap<&'a str, serde_json::Value>,
     writer: &'a mut dyn Write,
+    stringify_big_ints: bool,
 }
 
 impl<'a> fmt::Debug for JsonVisitor<'a> {
@@ -393,6 +688,17 @@ impl<'a> JsonVisitor<'a> {
         Self {
             values: BTreeMap::new(),
             writer,
+            stringify_big_ints: false,
+        }
+    }
+
+    /// If set to `true`, integer field values that can't be represented
+    /// exactly as a double-precision float are recorded as JSON strings
+    /// rather than numbers. See [`JsonFields::with_stringified_big_ints`].
+    pub fn with_stringified_big_ints(self, stringify_big_ints: bool) -> Self {
+        Self {
+            stringify_big_ints,
+            ..self
         }
     }
 }
@@ -433,12 +739,22 @@ impl<'a> field::Visit for JsonVisitor<'a> {
 
     /// Visit a signed 64-bit integer value.
     fn record_i64(&mut self, field: &Field, value: i64) {
+        if self.stringify_big_ints && (value as i128).abs() > MAX_SAFE_INT_MAGNITUDE {
+            self.values
+                .insert(field.name(), serde_json::Value::from(value.to_string()));
+            return;
+        }
         self.values
             .insert(field.name(), serde_json::Value::from(value));
     }
 
     /// Visit an unsigned 64-bit integer value.
     fn record_u64(&mut self, field: &Field, value: u64) {
+        if self.stringify_big_ints && value as u128 > MAX_SAFE_INT_MAGNITUDE as u128 {
+            self.values
+                .insert(field.name(), serde_json::Value::from(value.to_string()));
+            return;
+        }
         self.values
             .insert(field.name(), serde_json::Value::from(value));
     }
@@ -652,6 +968,110 @@ mod test {
         });
     }
 
+    #[test]
+    fn json_stringifies_large_event_integers() {
+        let buffer = MockMakeWriter::default();
+        let subscriber = crate::fmt()
+            .json()
+            .with_stringified_big_ints(true)
+            .with_writer(buffer.clone())
+            .finish();
+
+        with_default(subscriber, || {
+            tracing::info!(small = 42, big = 1i64 << 60, big_unsigned = 1u64 << 60);
+        });
+
+        let json = parse_as_json(&buffer);
+        assert_eq!(json["fields"]["small"], 42);
+        assert_eq!(json["fields"]["big"], (1i64 << 60).to_string());
+        assert_eq!(json["fields"]["big_unsigned"], (1u64 << 60).to_string());
+    }
+
+    #[test]
+    fn json_stringifies_large_event_integers_flattened() {
+        let buffer = MockMakeWriter::default();
+        let subscriber = crate::fmt()
+            .json()
+            .flatten_event(true)
+            .with_stringified_big_ints(true)
+            .with_writer(buffer.clone())
+            .finish();
+
+        with_default(subscriber, || {
+            tracing::info!(small = 42, big = 1i64 << 60);
+        });
+
+        let json = parse_as_json(&buffer);
+        assert_eq!(json["small"], 42);
+        assert_eq!(json["big"], (1i64 << 60).to_string());
+    }
+
+    #[test]
+    fn json_stringifies_large_span_integers() {
+        let buffer = MockMakeWriter::default();
+        let subscriber = crate::fmt()
+            .json()
+            .fmt_fields(JsonFields::new().with_stringified_big_ints(true))
+            .with_writer(buffer.clone())
+            .finish();
+
+        with_default(subscriber, || {
+            let span = tracing::info_span!("s", small = 42, big = 1i64 << 60);
+            let _enter = span.enter();
+            tracing::info!("event");
+        });
+
+        let json = parse_as_json(&buffer);
+        assert_eq!(json["span"]["small"], 42);
+        assert_eq!(json["span"]["big"], (1i64 << 60).to_string());
+    }
+
+    #[test]
+    fn json_inherits_field_defaults_from_spans() {
+        let buffer = MockMakeWriter::default();
+        let subscriber = crate::fmt()
+            .json()
+            .with_inherited_field_defaults(true)
+            .with_writer(buffer.clone())
+            .finish();
+
+        with_default(subscriber, || {
+            let outer = tracing::info_span!("outer", component = "db");
+            let _outer_enter = outer.enter();
+            let inner = tracing::info_span!("inner", request_id = 42);
+            let _inner_enter = inner.enter();
+
+            tracing::info!("did a thing");
+        });
+
+        let json = parse_as_json(&buffer);
+        assert_eq!(json["fields"]["message"], "did a thing");
+        assert_eq!(json["fields"]["component"], "db");
+        assert_eq!(json["fields"]["request_id"], 42);
+    }
+
+    #[test]
+    fn json_inherited_field_defaults_are_overridden_by_nearer_values() {
+        let buffer = MockMakeWriter::default();
+        let subscriber = crate::fmt()
+            .json()
+            .with_inherited_field_defaults(true)
+            .with_writer(buffer.clone())
+            .finish();
+
+        with_default(subscriber, || {
+            let outer = tracing::info_span!("outer", component = "db");
+            let _outer_enter = outer.enter();
+            let inner = tracing::info_span!("inner", component = "cache");
+            let _inner_enter = inner.enter();
+
+            tracing::info!(component = "handler", "did a thing");
+        });
+
+        let json = parse_as_json(&buffer);
+        assert_eq!(json["fields"]["component"], "handler");
+    }
+
     #[test]
     fn json_span_event_show_correct_context() {
         let buffer = MockMakeWriter::default();
@@ -738,6 +1158,51 @@ mod test {
         });
     }
 
+    #[cfg(feature = "trace-context")]
+    #[test]
+    fn json_includes_trace_and_span_ids() {
+        use crate::registry::trace_context::TraceContextLayer;
+        use crate::subscribe::{CollectExt, Layered};
+        use crate::Registry;
+
+        let buffer = MockMakeWriter::default();
+        let subscriber = crate::registry().with(TraceContextLayer::new()).with(
+            crate::fmt::subscriber::<Layered<TraceContextLayer, Registry>>()
+                .json()
+                .with_writer(buffer.clone())
+                .with_trace_context(true),
+        );
+
+        with_default(subscriber, || {
+            let span = tracing::info_span!("traced");
+            let _enter = span.enter();
+            tracing::info!("some traced event");
+        });
+
+        let json = parse_as_json(&buffer);
+        assert_eq!(json["trace_id"].as_str().unwrap().len(), 32);
+        assert_eq!(json["span_id"].as_str().unwrap().len(), 16);
+    }
+
+    #[cfg(feature = "trace-context")]
+    #[test]
+    fn json_omits_trace_and_span_ids_without_a_trace_context() {
+        let buffer = MockMakeWriter::default();
+        let subscriber = crate::fmt()
+            .json()
+            .with_writer(buffer.clone())
+            .with_trace_context(true)
+            .finish();
+
+        with_default(subscriber, || {
+            tracing::info!("no trace context layer is installed");
+        });
+
+        let json = parse_as_json(&buffer);
+        assert!(json.get("trace_id").is_none());
+        assert!(json.get("span_id").is_none());
+    }
+
     fn parse_as_json(buffer: &MockMakeWriter) -> serde_json::Value {
         let buf = String::from_utf8(buffer.buf().to_vec()).unwrap();
         let json = buf
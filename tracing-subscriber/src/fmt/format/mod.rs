@@ -34,6 +34,9 @@ mod pretty;
 #[cfg_attr(docsrs, doc(cfg(feature = "ansi")))]
 pub use pretty::*;
 
+mod template;
+pub use template::*;
+
 use fmt::{Debug, Display};
 
 /// A type that can format a tracing `Event` for a `fmt::Write`.
@@ -358,6 +361,28 @@ impl<F, T> Format<F, T> {
         }
     }
 
+    /// Use a user-supplied [template string](Template) to format events,
+    /// instead of a fixed layout.
+    ///
+    /// See [`Template`] for the supported placeholders.
+    ///
+    /// Note that [`Format::with_target`], [`Format::with_level`],
+    /// [`Format::without_time`], and the other shared display toggles have no
+    /// effect once a template is in use; reference `{target}`, `{level}`, or
+    /// `{ts}` directly in the template string instead.
+    pub fn with_template(self, template: impl AsRef<str>) -> Format<Template, T> {
+        Format {
+            format: Template::new(template),
+            timer: self.timer,
+            ansi: self.ansi,
+            display_target: self.display_target,
+            display_timestamp: self.display_timestamp,
+            display_level: self.display_level,
+            display_thread_id: self.display_thread_id,
+            display_thread_name: self.display_thread_name,
+        }
+    }
+
     /// Use the given [`timer`] for log message timestamps.
     ///
     /// See [`time` module] for the provided timer implementations.
@@ -539,6 +564,44 @@ impl<T> Format<Json, T> {
         self.format.with_span_list(display_span_list);
         self
     }
+
+    /// Sets whether or not the formatter will stringify `i64`/`u64` event
+    /// field values that can't be represented exactly as a double-precision
+    /// float.
+    ///
+    /// See [`Json::with_stringified_big_ints`].
+    #[cfg(feature = "json")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "json")))]
+    pub fn with_stringified_big_ints(mut self, stringify_big_ints: bool) -> Format<Json, T> {
+        self.format.with_stringified_big_ints(stringify_big_ints);
+        self
+    }
+
+    /// Sets whether or not fields recorded on a span are inherited as
+    /// default values by events recorded inside that span (and its
+    /// descendants).
+    ///
+    /// See [`Json::with_inherited_field_defaults`].
+    #[cfg(feature = "json")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "json")))]
+    pub fn with_inherited_field_defaults(mut self, inherit_field_defaults: bool) -> Format<Json, T> {
+        self.format
+            .with_inherited_field_defaults(inherit_field_defaults);
+        self
+    }
+
+    /// Sets whether or not the formatter will include `trace_id` and
+    /// `span_id` fields taken from the current span's [`TraceContext`].
+    ///
+    /// See [`Json::with_trace_context`].
+    ///
+    /// [`TraceContext`]: crate::registry::trace_context::TraceContext
+    #[cfg(feature = "trace-context")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "trace-context")))]
+    pub fn with_trace_context(mut self, display_trace_context: bool) -> Format<Json, T> {
+        self.format.with_trace_context(display_trace_context);
+        self
+    }
 }
 
 impl<C, N, T> FormatEvent<C, N> for Format<Full, T>
@@ -586,7 +649,7 @@ where
             let mut seen = false;
 
             for span in scope.from_root() {
-                write!(writer, "{}", bold.paint(span.metadata().name()))?;
+                write!(writer, "{}", bold.paint(span.display_name()))?;
                 seen = true;
 
                 let ext = span.extensions();
@@ -186,21 +186,20 @@ where
         let scope = span.into_iter().flat_map(|span| span.scope());
 
         for span in scope {
-            let meta = span.metadata();
             if self.display_target {
                 write!(
                     writer,
                     "    {} {}::{}",
                     dimmed.paint("in"),
-                    meta.target(),
-                    bold.paint(meta.name()),
+                    span.display_target(),
+                    bold.paint(span.display_name()),
                 )?;
             } else {
                 write!(
                     writer,
                     "    {} {}",
                     dimmed.paint("in"),
-                    bold.paint(meta.name()),
+                    bold.paint(span.display_name()),
                 )?;
             }
 
@@ -0,0 +1,256 @@
+use super::{Format, FormatEvent, FormatFields, FormatTime};
+use crate::{fmt::fmt_subscriber::FmtContext, registry::LookupSpan};
+use std::{collections::BTreeMap, fmt};
+use tracing_core::{field, Collect, Event};
+
+#[cfg(feature = "span-timing")]
+use crate::registry::timing::SpanTimings;
+
+/// Marker for `Format` that indicates that events should be rendered using a
+/// user-supplied template string, referencing the event's fields and a
+/// handful of well-known properties by name.
+///
+/// Unlike the other formats, a [`Template`] fully controls the layout of
+/// each line: [`Format::with_target`], [`Format::with_level`],
+/// [`Format::without_time`], and the other shared display toggles have no
+/// effect on it. Reference `{target}`, `{level}`, or `{ts}` directly in the
+/// template string instead.
+///
+/// # Placeholders
+///
+/// - `{ts}` -- the event's timestamp, formatted with the [`Format`]'s configured timer.
+/// - `{level}` -- the event's level (`INFO`, `WARN`, ...).
+/// - `{target}` -- the event's target.
+/// - `{fields.NAME}` -- the value of the event field named `NAME`, or nothing if the
+///   event didn't record that field.
+/// - `{span.duration_ms}` -- the busy plus idle time, in milliseconds, that a
+///   [`SpanTiming`] subscriber has recorded for the nearest enclosing span, or
+///   nothing if none was recorded. Only recognized when the "span-timing"
+///   feature is enabled; other `{span.*}` properties aren't supported, since a
+///   span's own fields are only retained as pre-formatted text, not as
+///   structured values a template could look up by name.
+///
+/// Anything else inside `{}`, including a typo'd placeholder, is written out
+/// literally, braces and all, rather than silently dropped.
+///
+/// # Examples
+///
+/// ```rust
+/// let format = tracing_subscriber::fmt::format()
+///     .with_template("{ts} {level} {fields.method} {fields.path} {fields.status}");
+/// ```
+///
+/// [`SpanTiming`]: crate::registry::timing::SpanTiming
+#[derive(Debug, Clone)]
+pub struct Template {
+    parts: Vec<Part>,
+}
+
+#[derive(Debug, Clone)]
+enum Part {
+    Literal(String),
+    Timestamp,
+    Level,
+    Target,
+    Field(String),
+    #[cfg(feature = "span-timing")]
+    SpanDurationMs,
+}
+
+impl Template {
+    /// Parses `template` into a new `Template`.
+    ///
+    /// See the [type-level docs](Template) for the supported placeholders.
+    pub fn new(template: impl AsRef<str>) -> Self {
+        let template = template.as_ref();
+        let mut parts = Vec::new();
+        let mut literal = String::new();
+        let mut chars = template.chars();
+
+        while let Some(c) = chars.next() {
+            if c != '{' {
+                literal.push(c);
+                continue;
+            }
+
+            if !literal.is_empty() {
+                parts.push(Part::Literal(std::mem::take(&mut literal)));
+            }
+
+            let mut name = String::new();
+            let mut closed = false;
+            for c in chars.by_ref() {
+                if c == '}' {
+                    closed = true;
+                    break;
+                }
+                name.push(c);
+            }
+
+            parts.push(if closed {
+                Part::from_name(&name)
+            } else {
+                // An unterminated `{` at the end of the template: keep it (and
+                // whatever followed it) as a literal rather than dropping it.
+                Part::Literal(format!("{{{}", name))
+            });
+        }
+
+        if !literal.is_empty() {
+            parts.push(Part::Literal(literal));
+        }
+
+        Self { parts }
+    }
+}
+
+impl Part {
+    fn from_name(name: &str) -> Self {
+        match name {
+            "ts" => Part::Timestamp,
+            "level" => Part::Level,
+            "target" => Part::Target,
+            #[cfg(feature = "span-timing")]
+            "span.duration_ms" => Part::SpanDurationMs,
+            _ => match name.strip_prefix("fields.") {
+                Some(field) => Part::Field(field.to_string()),
+                None => Part::Literal(format!("{{{}}}", name)),
+            },
+        }
+    }
+}
+
+/// Records an event's fields into an owned map keyed by field name, so a
+/// [`Template`]'s `{fields.NAME}` placeholders can look any of them up by
+/// name, in any order, rather than only being able to stream them out in
+/// recorded order the way [`super::DefaultVisitor`] does.
+struct FieldMap(BTreeMap<&'static str, String>);
+
+impl FieldMap {
+    fn record(event: &Event<'_>) -> Self {
+        let mut map = BTreeMap::new();
+        event.record(&mut Visitor(&mut map));
+        Self(map)
+    }
+}
+
+struct Visitor<'a>(&'a mut BTreeMap<&'static str, String>);
+
+impl<'a> field::Visit for Visitor<'a> {
+    fn record_str(&mut self, field: &field::Field, value: &str) {
+        self.0.insert(field.name(), value.to_string());
+    }
+
+    fn record_debug(&mut self, field: &field::Field, value: &dyn fmt::Debug) {
+        self.0.insert(field.name(), format!("{:?}", value));
+    }
+}
+
+impl<C, N, T> FormatEvent<C, N> for Format<Template, T>
+where
+    C: Collect + for<'lookup> LookupSpan<'lookup>,
+    N: for<'writer> FormatFields<'writer> + 'static,
+    T: FormatTime,
+{
+    fn format_event(
+        &self,
+        #[cfg_attr(not(feature = "span-timing"), allow(unused_variables))] ctx: &FmtContext<
+            '_,
+            C,
+            N,
+        >,
+        writer: &mut dyn fmt::Write,
+        event: &Event<'_>,
+    ) -> fmt::Result {
+        let mut fields = None;
+
+        for part in &self.format.parts {
+            match part {
+                Part::Literal(s) => writer.write_str(s)?,
+                Part::Timestamp => self.timer.format_time(writer)?,
+                Part::Level => write!(writer, "{}", event.metadata().level())?,
+                Part::Target => writer.write_str(event.metadata().target())?,
+                Part::Field(name) => {
+                    let fields = fields.get_or_insert_with(|| FieldMap::record(event));
+                    if let Some(value) = fields.0.get(name.as_str()) {
+                        writer.write_str(value)?;
+                    }
+                }
+                #[cfg(feature = "span-timing")]
+                Part::SpanDurationMs => {
+                    if let Some(millis) = ctx
+                        .ctx
+                        .event_scope(event)
+                        .and_then(|mut scope| scope.next())
+                        .and_then(|span| {
+                            let ext = span.extensions();
+                            ext.get::<SpanTimings>()
+                                .map(|timings| (timings.busy + timings.idle).as_millis())
+                        })
+                    {
+                        write!(writer, "{}", millis)?;
+                    }
+                }
+            }
+        }
+
+        writeln!(writer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fmt::test::MockMakeWriter;
+    use tracing::collect::with_default;
+
+    #[test]
+    fn parses_literals_and_placeholders() {
+        let template = Template::new("{level} {target}: {fields.message}");
+        assert_eq!(template.parts.len(), 5);
+    }
+
+    #[test]
+    fn unknown_placeholder_is_kept_literal() {
+        let template = Template::new("{nope}");
+        assert_eq!(template.parts.len(), 1);
+        match &template.parts[0] {
+            Part::Literal(s) => assert_eq!(s, "{nope}"),
+            other => panic!("expected a literal part, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn renders_access_log_style_line() {
+        let make_writer = MockMakeWriter::default();
+        let collector = crate::fmt::CollectorBuilder::default()
+            .with_template("{level} {fields.method} {fields.path} {fields.status}")
+            .with_writer(make_writer.clone())
+            .finish();
+
+        with_default(collector, || {
+            tracing::info!(method = "GET", path = "/users", status = 200, "handled");
+        });
+
+        let buf = make_writer.buf();
+        let actual = std::str::from_utf8(&buf[..]).unwrap();
+        assert_eq!(actual, "INFO GET /users 200\n");
+    }
+
+    #[test]
+    fn missing_field_renders_as_nothing() {
+        let make_writer = MockMakeWriter::default();
+        let collector = crate::fmt::CollectorBuilder::default()
+            .with_template("status={fields.status}")
+            .with_writer(make_writer.clone())
+            .finish();
+
+        with_default(collector, || {
+            tracing::info!("no status field here");
+        });
+
+        let buf = make_writer.buf();
+        let actual = std::str::from_utf8(&buf[..]).unwrap();
+        assert_eq!(actual, "status=\n");
+    }
+}
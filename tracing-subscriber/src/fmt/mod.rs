@@ -415,6 +415,28 @@ pub fn subscriber<C>() -> Subscriber<C> {
     Subscriber::default()
 }
 
+/// Returns a new [`CollectorBuilder`] preconfigured for human-friendly
+/// command-line output: no timestamps (a CLI's own output is read as it's
+/// printed, so a timestamp on every line is noise rather than context) and,
+/// when the "ansi" feature is enabled, colored level glyphs.
+///
+/// This is a shorthand for the equivalent [`fmt`] and [`CollectorBuilder`]
+/// calls:
+///
+/// ```rust
+/// let collector = tracing_subscriber::fmt()
+///     .without_time()
+///     .with_ansi(true);
+/// ```
+///
+/// This function requires the "cli" feature flag, which also enables
+/// "ansi".
+#[cfg(feature = "cli")]
+#[cfg_attr(docsrs, doc(cfg(feature = "cli")))]
+pub fn cli() -> CollectorBuilder<format::DefaultFields, format::Format<format::Full, ()>> {
+    fmt().without_time().with_ansi(true)
+}
+
 impl Collector {
     /// The maximum [verbosity level] that is enabled by a `Collector` by
     /// default.
@@ -756,6 +778,23 @@ where
         }
     }
 
+    /// Sets the collector being built to format events using a user-supplied
+    /// [template string](format::Template), instead of a fixed layout.
+    ///
+    /// See [`format::Template`] for the supported placeholders.
+    pub fn with_template(
+        self,
+        template: impl AsRef<str>,
+    ) -> CollectorBuilder<N, format::Format<format::Template, T>, F, W>
+    where
+        N: for<'writer> FormatFields<'writer> + 'static,
+    {
+        CollectorBuilder {
+            filter: self.filter,
+            inner: self.inner.with_template(template),
+        }
+    }
+
     /// Sets the collector being built to use an [excessively pretty, human-readable formatter](crate::fmt::format::Pretty).
     #[cfg(feature = "ansi")]
     #[cfg_attr(docsrs, doc(cfg(feature = "ansi")))]
@@ -827,6 +866,57 @@ impl<T, F, W> CollectorBuilder<format::JsonFields, format::Format<format::Json,
             inner: self.inner.with_span_list(display_span_list),
         }
     }
+
+    /// Sets whether or not the JSON collector being built will stringify
+    /// `i64`/`u64` event field values that can't be represented exactly as a
+    /// double-precision float.
+    ///
+    /// See [`format::Json::with_stringified_big_ints`](super::fmt::format::Json::with_stringified_big_ints).
+    pub fn with_stringified_big_ints(
+        self,
+        stringify_big_ints: bool,
+    ) -> CollectorBuilder<format::JsonFields, format::Format<format::Json, T>, F, W> {
+        CollectorBuilder {
+            filter: self.filter,
+            inner: self.inner.with_stringified_big_ints(stringify_big_ints),
+        }
+    }
+
+    /// Sets whether or not fields recorded on a span are inherited as
+    /// default values by events recorded inside that span (and its
+    /// descendants).
+    ///
+    /// See [`format::Json::with_inherited_field_defaults`](super::fmt::format::Json::with_inherited_field_defaults).
+    pub fn with_inherited_field_defaults(
+        self,
+        inherit_field_defaults: bool,
+    ) -> CollectorBuilder<format::JsonFields, format::Format<format::Json, T>, F, W> {
+        CollectorBuilder {
+            filter: self.filter,
+            inner: self
+                .inner
+                .with_inherited_field_defaults(inherit_field_defaults),
+        }
+    }
+
+    /// Sets whether or not the JSON collector being built will include
+    /// `trace_id` and `span_id` fields taken from the current span's
+    /// [`TraceContext`].
+    ///
+    /// See [`format::Json::with_trace_context`](super::fmt::format::Json::with_trace_context).
+    ///
+    /// [`TraceContext`]: crate::registry::trace_context::TraceContext
+    #[cfg(feature = "trace-context")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "trace-context")))]
+    pub fn with_trace_context(
+        self,
+        display_trace_context: bool,
+    ) -> CollectorBuilder<format::JsonFields, format::Format<format::Json, T>, F, W> {
+        CollectorBuilder {
+            filter: self.filter,
+            inner: self.inner.with_trace_context(display_trace_context),
+        }
+    }
 }
 
 impl<N, E, F, W> CollectorBuilder<N, E, reload::Subscriber<F>, W>
@@ -1087,6 +1177,91 @@ impl<N, E, F, W> CollectorBuilder<N, E, F, W> {
             inner: self.inner.with_writer(TestWriter::default()),
         }
     }
+
+    /// Adds a per-layer [`Filter`] to the formatting layer being built.
+    ///
+    /// Unlike [`with_env_filter`] and [`with_max_level`], which apply a
+    /// filter to the entire collector, this uses [per-layer filtering], so
+    /// the formatting layer can be filtered independently of any other
+    /// layers it is combined with (for example, via [`with_extra_layer`]).
+    ///
+    /// Because a per-layer filter changes the type of the resulting
+    /// subscriber, this consumes the `CollectorBuilder` and returns the
+    /// composed [`Filtered`] subscriber, rather than a `CollectorBuilder`.
+    /// Call [`Subscribe::with_collector`] on the result to finish building a
+    /// collector, or [`Subscribe::and_then`] to add further layers first.
+    ///
+    /// [`Filter`]: crate::subscribe::Filter
+    /// [`with_env_filter`]: CollectorBuilder::with_env_filter
+    /// [`with_max_level`]: CollectorBuilder::with_max_level
+    /// [per-layer filtering]: crate::subscribe#filtering-with-subscribes
+    /// [`with_extra_layer`]: CollectorBuilder::with_extra_layer
+    /// [`Filtered`]: crate::filter::Filtered
+    /// [`Subscribe::with_collector`]: crate::subscribe::Subscribe::with_collector
+    /// [`Subscribe::and_then`]: crate::subscribe::Subscribe::and_then
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use tracing_subscriber::{fmt, filter::LevelFilter, subscribe::Subscribe, Registry};
+    ///
+    /// let collector = fmt()
+    ///     .compact()
+    ///     .with_filter(LevelFilter::INFO)
+    ///     .with_collector(Registry::default());
+    /// ```
+    pub fn with_filter<Fl>(
+        self,
+        filter: Fl,
+    ) -> crate::filter::Filtered<Subscriber<Registry, N, E, W>, Fl, Registry>
+    where
+        Subscriber<Registry, N, E, W>: subscribe::Subscribe<Registry>,
+        Fl: crate::filter::Filter<Registry>,
+    {
+        self.inner.with_filter(filter)
+    }
+
+    /// Adds an additional [`Subscribe`] on top of the formatting layer being
+    /// built.
+    ///
+    /// This lets the easy [`fmt`] path scale to slightly-more-complex
+    /// setups (for example, adding a metrics or `OpenTelemetry` layer)
+    /// without abandoning it for the [`registry`] API.
+    ///
+    /// Because adding a layer changes the type of the resulting subscriber,
+    /// this consumes the `CollectorBuilder` and returns the composed
+    /// [`Layered`] subscriber, rather than a `CollectorBuilder`. Call
+    /// [`Subscribe::with_collector`] on the result to finish building a
+    /// collector, or chain further [`with_extra_layer`]/[`with_filter`]
+    /// calls first.
+    ///
+    /// [`Subscribe`]: crate::subscribe::Subscribe
+    /// [`fmt`]: mod@self
+    /// [`registry`]: crate::registry
+    /// [`Layered`]: crate::subscribe::Layered
+    /// [`Subscribe::with_collector`]: crate::subscribe::Subscribe::with_collector
+    /// [`with_extra_layer`]: CollectorBuilder::with_extra_layer
+    /// [`with_filter`]: CollectorBuilder::with_filter
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use tracing_subscriber::{fmt, filter::LevelFilter, subscribe::Subscribe, Registry};
+    ///
+    /// let collector = fmt()
+    ///     .with_extra_layer(fmt::subscriber::<Registry>().with_filter(LevelFilter::WARN))
+    ///     .with_collector(Registry::default());
+    /// ```
+    pub fn with_extra_layer<L>(
+        self,
+        layer: L,
+    ) -> subscribe::Layered<L, Subscriber<Registry, N, E, W>, Registry>
+    where
+        Subscriber<Registry, N, E, W>: subscribe::Subscribe<Registry>,
+        L: subscribe::Subscribe<Registry>,
+    {
+        self.inner.and_then(layer)
+    }
 }
 
 /// Install a global tracing collector that listens for events and
@@ -1146,6 +1321,69 @@ pub fn init() {
     try_init().expect("Unable to install global collector")
 }
 
+/// Install a global tracing collector as in [`init`], but if that fails
+/// (likely because a global collector was already installed), fall back to
+/// installing a minimal collector that writes to stderr, rather than
+/// panicking.
+///
+/// This is intended for libraries that want to ensure some diagnostics are
+/// visible when embedded in a host application, without risking aborting
+/// that host if the host has already installed its own collector.
+///
+/// If installing the fallback collector also fails, this has no effect;
+/// it will not panic, since a collector --- either the host's or this
+/// library's --- is already installed in that case.
+pub fn init_or_fallback() {
+    if let Err(error) = try_init() {
+        eprintln!(
+            "failed to install global default subscriber, falling back to a \
+             minimal stderr subscriber: {}",
+            error
+        );
+        let _ = fmt().with_writer(io::stderr).try_init();
+    }
+}
+
+/// Sets a collector, scoped to the current thread, that cooperates with
+/// `cargo test`'s output capturing.
+///
+/// This is meant to be called at the start of a test, typically as the
+/// first line of the test function. It uses [`TestWriter`] rather than
+/// [`io::stdout`], so output is captured per test thread by `libtest` and
+/// is only printed for tests that fail, and it returns a guard that resets
+/// the thread's default collector when the test ends, rather than
+/// installing a process-wide global collector. This avoids the collisions
+/// that come from every test in a process racing to call
+/// [`init`]/[`try_init`] on the same global default.
+///
+/// The collector filters events based on the `RUST_LOG_TEST` environment
+/// variable, using the same directive syntax as [`EnvFilter`]. Unlike
+/// `RUST_LOG`, this is a separate variable so that enabling logs in test
+/// runs doesn't also turn them on for the same binary run outside of
+/// `cargo test`.
+///
+/// # Examples
+///
+/// ```rust
+/// #[test]
+/// fn my_test() {
+///     let _guard = tracing_subscriber::fmt::init_for_tests();
+///     // ... test code that emits `tracing` events ...
+/// }
+/// ```
+///
+/// [`EnvFilter`]: super::filter::EnvFilter
+#[cfg(feature = "env-filter")]
+#[cfg_attr(docsrs, doc(cfg(feature = "env-filter")))]
+pub fn init_for_tests() -> tracing_core::dispatch::DefaultGuard {
+    use crate::util::SubscriberInitExt;
+
+    fmt()
+        .with_test_writer()
+        .with_env_filter(crate::EnvFilter::from_env("RUST_LOG_TEST"))
+        .set_default()
+}
+
 #[cfg(test)]
 mod test {
     use crate::{
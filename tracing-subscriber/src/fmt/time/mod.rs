@@ -134,6 +134,58 @@ impl FormatTime for Uptime {
     }
 }
 
+/// Formats timestamps as a monotonic offset from a wall-clock anchor taken
+/// when the timer was constructed.
+///
+/// Unlike [`SystemTime`], which re-reads the wall clock on every event,
+/// `MonotonicAnchored` reads the wall clock exactly once (at construction)
+/// and thereafter reconstructs each event's timestamp by adding a
+/// [`std::time::Instant`]-based offset to that anchor. Events recorded this
+/// way stay correctly ordered relative to one another even if the system
+/// clock is stepped backward or forward (e.g. by NTP) while the process is
+/// running, since the monotonic clock used for the offset is never affected
+/// by such adjustments. The printed timestamp may drift from true wall time
+/// across a clock step, but relative ordering between events is preserved.
+#[derive(Debug, Clone)]
+pub struct MonotonicAnchored {
+    anchor_wall: std::time::SystemTime,
+    anchor_mono: Instant,
+}
+
+impl Default for MonotonicAnchored {
+    fn default() -> Self {
+        Self {
+            anchor_wall: std::time::SystemTime::now(),
+            anchor_mono: Instant::now(),
+        }
+    }
+}
+
+impl MonotonicAnchored {
+    /// Returns a new timer, anchoring the current wall-clock and monotonic
+    /// time.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl FormatTime for MonotonicAnchored {
+    fn format_time(&self, w: &mut dyn fmt::Write) -> fmt::Result {
+        let now = self.anchor_wall + self.anchor_mono.elapsed();
+        let now: chrono::DateTime<chrono::Utc> = now.into();
+        write!(w, "{}", now.format("%b %d %H:%M:%S%.3f"))
+    }
+}
+
+#[cfg(not(feature = "chrono"))]
+impl FormatTime for MonotonicAnchored {
+    fn format_time(&self, w: &mut dyn fmt::Write) -> fmt::Result {
+        let now = self.anchor_wall + self.anchor_mono.elapsed();
+        write!(w, "{}", datetime::DateTime::from(now))
+    }
+}
+
 /// The RFC 3339 format is used by default and using
 /// this struct allows chrono to bypass the parsing
 /// used when a custom format string is provided
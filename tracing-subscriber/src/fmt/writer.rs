@@ -1,11 +1,28 @@
 //! Abstractions for creating [`io::Write`] instances.
 //!
 //! [`io::Write`]: std::io::Write
+//!
+//! # Non-blocking Writers
+//!
+//! The [`MakeWriter`]s in this module all write synchronously, on the thread
+//! that's recording the event. If writing blocks that thread for too long
+//! (for example, because the destination is a slow disk or a network
+//! socket), consider the `tracing-appender` crate's
+//! [`NonBlocking`][non-blocking] writer instead, which moves writing to a
+//! dedicated background thread and implements [`MakeWriter`] so it can be
+//! passed to [`with_writer`] just like the writers here.
+//!
+//! [non-blocking]: https://docs.rs/tracing-appender/latest/tracing_appender/non_blocking/struct.NonBlocking.html
+//! [`with_writer`]: crate::fmt::SubscriberBuilder::with_writer
 
 use std::{
     fmt::Debug,
     io::{self, Write},
-    sync::{Mutex, MutexGuard},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex, MutexGuard,
+    },
+    time::{Duration, Instant},
 };
 use tracing_core::Metadata;
 
@@ -229,6 +246,30 @@ pub trait MakeWriter<'a> {
 /// This is not intended to be implemented directly for user-defined
 /// [`MakeWriter`]s; instead, it should be imported when the desired methods are
 /// used.
+///
+/// # Examples
+///
+/// Errors to stderr, everything else to stdout, and a specific target to its
+/// own file, all within a single [`fmt::Layer`](crate::fmt::Layer):
+///
+/// ```
+/// use tracing::Level;
+/// use tracing_subscriber::fmt::writer::MakeWriterExt;
+/// # fn docs() -> std::io::Result<()> {
+/// let access_log = std::fs::File::create("access.log")?;
+///
+/// let mk_writer = std::io::stderr
+///     .with_max_level(Level::WARN)
+///     .or_else(
+///         access_log
+///             .with_filter(|meta| meta.target() == "http::access_log")
+///             .or_else(std::io::stdout),
+///     );
+///
+/// tracing_subscriber::fmt().with_writer(mk_writer).init();
+/// # Ok(())
+/// # }
+/// ```
 pub trait MakeWriterExt<'a>: MakeWriter<'a> {
     /// Wraps `self` and returns a [`MakeWriter`] that will only write output
     /// for events at or below the provided verbosity [`Level`]. For instance,
@@ -471,6 +512,45 @@ pub trait MakeWriterExt<'a>: MakeWriter<'a> {
     {
         OrElse::new(self, other)
     }
+
+    /// Combines `self` with a fallback [`MakeWriter`], returning a new
+    /// [`MakeWriter`] that fails over to the fallback after `self`'s writer
+    /// returns errors on several consecutive writes, and periodically
+    /// re-probes `self` to see if it has recovered.
+    ///
+    /// This differs from [`or_else`][Self::or_else] in that the fallback
+    /// decision is made based on runtime write *errors*, rather than on a
+    /// [`MakeWriter`] declining to produce a writer at all (e.g. via a level
+    /// or target filter). It's meant for a primary writer that can fail at
+    /// write time, such as a writer backed by a network socket, with a
+    /// fallback that's expected to be reliable, such as a local file.
+    ///
+    /// By default, the fallback is used after 3 consecutive write errors, and
+    /// `self` is tried again after 30 seconds. These can be changed with
+    /// [`OrElseOnError::with_failure_threshold`] and
+    /// [`OrElseOnError::with_reprobe_interval`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tracing_subscriber::fmt::writer::MakeWriterExt;
+    ///
+    /// # fn docs() -> std::io::Result<()> {
+    /// let primary = std::fs::File::create("/dev/network-sink")?;
+    /// let fallback = std::fs::File::create("/tmp/fallback.log")?;
+    /// let mk_writer = std::sync::Mutex::new(primary).or_else_on_error(std::sync::Mutex::new(fallback));
+    ///
+    /// tracing_subscriber::fmt().with_writer(mk_writer).init();
+    /// # Ok(())
+    /// # }
+    /// ```
+    fn or_else_on_error<B>(self, fallback: B) -> OrElseOnError<Self, B>
+    where
+        Self: Sized,
+        B: MakeWriter<'a> + Sized,
+    {
+        OrElseOnError::new(self, fallback)
+    }
 }
 
 /// A type implementing [`io::Write`] for a [`MutexGuard`] where the type
@@ -631,6 +711,75 @@ pub struct Tee<A, B> {
     b: B,
 }
 
+/// The default number of consecutive write errors [`OrElseOnError`] will
+/// tolerate from its primary [`MakeWriter`] before failing over.
+const DEFAULT_FAILURE_THRESHOLD: usize = 3;
+
+/// The default amount of time [`OrElseOnError`] waits after failing over
+/// before it will try writing to its primary [`MakeWriter`] again.
+const DEFAULT_REPROBE_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Tracks consecutive write failures shared between an [`OrElseOnError`] and
+/// the writers it has produced, so that a failure recorded by one writer is
+/// visible to the next one `make_writer` produces.
+#[derive(Debug)]
+struct FailoverState {
+    consecutive_failures: AtomicUsize,
+    tripped_at: Mutex<Option<Instant>>,
+}
+
+impl FailoverState {
+    fn new() -> Self {
+        Self {
+            consecutive_failures: AtomicUsize::new(0),
+            tripped_at: Mutex::new(None),
+        }
+    }
+
+    fn record_success(&self) {
+        self.consecutive_failures.store(0, Ordering::Relaxed);
+        *self.tripped_at.lock().unwrap() = None;
+    }
+
+    fn record_failure(&self, failure_threshold: usize) {
+        let failures = self.consecutive_failures.fetch_add(1, Ordering::Relaxed) + 1;
+        if failures >= failure_threshold {
+            *self.tripped_at.lock().unwrap() = Some(Instant::now());
+        }
+    }
+
+    fn is_tripped(&self, reprobe_after: Duration) -> bool {
+        match *self.tripped_at.lock().unwrap() {
+            Some(at) => at.elapsed() < reprobe_after,
+            None => false,
+        }
+    }
+}
+
+/// Combines a primary [`MakeWriter`] with a fallback, switching to the
+/// fallback once the primary has produced several consecutive write errors,
+/// and periodically re-probing the primary to see if it has recovered.
+///
+/// This is returned by the [`MakeWriterExt::or_else_on_error`] method. See
+/// the method documentation for details.
+///
+/// Unlike most of the other combinators in this module, `OrElseOnError` is
+/// stateful: it shares a failure counter across every writer it produces, so
+/// that errors observed by one call to [`MakeWriter::make_writer`] affect
+/// whether the *next* call returns a primary or fallback writer. This crate
+/// has no general-purpose "status" or health-reporting subsystem for
+/// surfacing that a failover happened; callers that need to observe
+/// transitions can wrap the primary or fallback [`MakeWriter`] themselves and
+/// log or record metrics from there.
+#[derive(Clone, Debug)]
+pub struct OrElseOnError<A, B> {
+    primary: A,
+    fallback: B,
+    state: Arc<FailoverState>,
+    failure_threshold: usize,
+    reprobe_after: Duration,
+}
+
 impl<'a, F, W> MakeWriter<'a> for F
 where
     F: Fn() -> W,
@@ -1080,6 +1229,129 @@ where
     }
 }
 
+// === impl OrElseOnError ===
+
+impl<A, B> OrElseOnError<A, B> {
+    /// Combines a primary and a fallback [`MakeWriter`].
+    ///
+    /// See the documentation for [`MakeWriterExt::or_else_on_error`] for
+    /// details.
+    pub fn new(primary: A, fallback: B) -> Self {
+        Self {
+            primary,
+            fallback,
+            state: Arc::new(FailoverState::new()),
+            failure_threshold: DEFAULT_FAILURE_THRESHOLD,
+            reprobe_after: DEFAULT_REPROBE_INTERVAL,
+        }
+    }
+
+    /// Sets the number of consecutive write errors from the primary
+    /// [`MakeWriter`] required before failing over to the fallback.
+    ///
+    /// By default, this is 3.
+    pub fn with_failure_threshold(mut self, failure_threshold: usize) -> Self {
+        self.failure_threshold = failure_threshold;
+        self
+    }
+
+    /// Sets how long to wait after failing over before trying the primary
+    /// [`MakeWriter`] again.
+    ///
+    /// By default, this is 30 seconds.
+    pub fn with_reprobe_interval(mut self, reprobe_after: Duration) -> Self {
+        self.reprobe_after = reprobe_after;
+        self
+    }
+}
+
+impl<'a, A, B> MakeWriter<'a> for OrElseOnError<A, B>
+where
+    A: MakeWriter<'a>,
+    B: MakeWriter<'a>,
+{
+    type Writer = OrElseOnErrorWriter<A::Writer, B::Writer>;
+
+    #[inline]
+    fn make_writer(&'a self) -> Self::Writer {
+        let inner = if self.state.is_tripped(self.reprobe_after) {
+            EitherWriter::B(self.fallback.make_writer())
+        } else {
+            EitherWriter::A(self.primary.make_writer())
+        };
+        OrElseOnErrorWriter {
+            inner,
+            state: self.state.clone(),
+            failure_threshold: self.failure_threshold,
+        }
+    }
+
+    #[inline]
+    fn make_writer_for(&'a self, meta: &Metadata<'_>) -> Self::Writer {
+        let inner = if self.state.is_tripped(self.reprobe_after) {
+            EitherWriter::B(self.fallback.make_writer_for(meta))
+        } else {
+            EitherWriter::A(self.primary.make_writer_for(meta))
+        };
+        OrElseOnErrorWriter {
+            inner,
+            state: self.state.clone(),
+            failure_threshold: self.failure_threshold,
+        }
+    }
+}
+
+/// The [writer] type produced by [`OrElseOnError`], which records write
+/// errors from the primary writer into the shared [`FailoverState`] so that
+/// later calls to [`OrElseOnError::make_writer`] can decide whether to fail
+/// over.
+///
+/// [writer]: std::io::Write
+#[derive(Debug)]
+pub struct OrElseOnErrorWriter<A, B> {
+    inner: EitherWriter<A, B>,
+    state: Arc<FailoverState>,
+    failure_threshold: usize,
+}
+
+impl<A, B> io::Write for OrElseOnErrorWriter<A, B>
+where
+    A: io::Write,
+    B: io::Write,
+{
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match &mut self.inner {
+            EitherWriter::A(primary) => match primary.write(buf) {
+                Ok(n) => {
+                    self.state.record_success();
+                    Ok(n)
+                }
+                Err(e) => {
+                    self.state.record_failure(self.failure_threshold);
+                    Err(e)
+                }
+            },
+            EitherWriter::B(fallback) => fallback.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match &mut self.inner {
+            EitherWriter::A(primary) => match primary.flush() {
+                Ok(()) => {
+                    self.state.record_success();
+                    Ok(())
+                }
+                Err(e) => {
+                    self.state.record_failure(self.failure_threshold);
+                    Err(e)
+                }
+            },
+            EitherWriter::B(fallback) => fallback.flush(),
+        }
+    }
+}
+
 // === blanket impls ===
 
 impl<'a, M> MakeWriterExt<'a> for M where M: MakeWriter<'a> {}
@@ -1345,4 +1617,38 @@ mod test {
         has_lines(&a_buf, &lines[..]);
         has_lines(&b_buf, &lines[..]);
     }
+
+    #[test]
+    fn combinators_or_else_on_error() {
+        struct AlwaysErrors;
+        impl io::Write for AlwaysErrors {
+            fn write(&mut self, _: &[u8]) -> io::Result<usize> {
+                Err(io::Error::new(io::ErrorKind::Other, "primary is down"))
+            }
+            fn flush(&mut self) -> io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let fallback_buf = Arc::new(Mutex::new(Vec::new()));
+        let fallback = MockMakeWriter::new(fallback_buf.clone());
+        let make_writer = (|| AlwaysErrors)
+            .or_else_on_error(fallback)
+            .with_failure_threshold(2);
+
+        // The primary is used until it has failed `failure_threshold` times,
+        // so the first write is lost to the always-failing primary...
+        let mut writer = make_writer.make_writer();
+        assert!(writer.write_all(b"first\n").is_err());
+        let mut writer = make_writer.make_writer();
+        assert!(writer.write_all(b"second\n").is_err());
+
+        // ...and once the threshold is reached, later writers fail over to
+        // the fallback.
+        let mut writer = make_writer.make_writer();
+        writer.write_all(b"third\n").expect("fallback should not error");
+
+        let actual = String::from_utf8(fallback_buf.try_lock().unwrap().to_vec()).unwrap();
+        assert_eq!(actual, "third\n");
+    }
 }
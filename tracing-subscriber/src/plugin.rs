@@ -0,0 +1,337 @@
+//! Layering a plugin's own subscriber on top of a host application's
+//! current dispatch, for the current thread, without replacing it.
+//!
+//! [`attach`] combines the thread's existing default [`Dispatch`] with a
+//! plugin-supplied one into a single [`Dispatch`] that forwards every span
+//! and event to both, and installs that combined dispatch as the new
+//! thread-local default until the returned guard is dropped. Each span is
+//! assigned a new id of its own; the host's and plugin's own ids for that
+//! span are recorded alongside it, so later `enter`/`exit`/`record` calls
+//! are routed to the right span in each collector, even though the two
+//! don't share an id space.
+//!
+//! This is meant for dynamically-loaded plugins that want to add their own
+//! sink (a file, a metrics exporter, a separate log stream) without
+//! reconfiguring, wrapping, or racing to replace the host's own dispatch --
+//! which a plugin typically has no way to reach into or rebuild, since it
+//! only ever sees it as an opaque [`Dispatch`].
+//!
+//! # Limitations
+//!
+//! An event recorded with an *explicit* parent span (via, e.g.,
+//! `event!(parent: id, ...)`) is forwarded to both dispatches without
+//! translating that parent id, since [`Event`]'s public API doesn't expose
+//! enough to reconstruct one with a different parent. Only events tied to
+//! whichever span is currently entered -- the overwhelmingly common case --
+//! are guaranteed to reach the right span in both collectors.
+//!
+//! This module requires the "plugin" feature flag.
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+
+use tracing_core::{
+    collect::Interest,
+    dispatch::{self, Dispatch},
+    span, Collect, Event, Metadata,
+};
+
+/// Combines `host` and `plugin` into a single [`Dispatch`] that forwards
+/// every span and event to both, and installs it as the default for the
+/// current thread until the returned guard is dropped.
+///
+/// The previous default (`host`) keeps receiving every span and event
+/// exactly as before; `plugin` receives the same, as a second, independent
+/// sink. See the [module-level docs](self) for how span ids are kept
+/// consistent between the two.
+///
+/// # Examples
+///
+/// ```
+/// use tracing_subscriber::plugin;
+///
+/// let plugins_own_subscriber = tracing_subscriber::registry();
+/// let _guard = plugin::attach(plugins_own_subscriber);
+/// tracing::info!("seen by both the host's dispatch and the plugin's");
+/// ```
+pub fn attach(plugin: impl Into<Dispatch>) -> dispatch::DefaultGuard {
+    let host = dispatch::get_default(|current| current.clone());
+    let broadcast = Dispatch::new(Broadcast::new(host, plugin.into()));
+    dispatch::set_default(&broadcast)
+}
+
+/// The host's and plugin's own ids for a span this [`Broadcast`] assigned an
+/// id of its own.
+struct SpanIds {
+    host: span::Id,
+    plugin: span::Id,
+    /// Mirrors the number of live handles to this span, so `try_close` is
+    /// only forwarded as "fully closed" once, in lockstep with the host's
+    /// and plugin's own refcounts (which only ever see this span through
+    /// calls this type forwards to them).
+    refs: AtomicU64,
+}
+
+/// A [`Collect`] that forwards every call to two inner dispatches, keeping
+/// each span's id in each of them straight. See the [module-level
+/// docs](self).
+struct Broadcast {
+    host: Dispatch,
+    plugin: Dispatch,
+    spans: Mutex<HashMap<u64, SpanIds>>,
+    by_host: Mutex<HashMap<u64, u64>>,
+    next_id: AtomicU64,
+}
+
+impl Broadcast {
+    fn new(host: Dispatch, plugin: Dispatch) -> Self {
+        Self {
+            host,
+            plugin,
+            spans: Mutex::new(HashMap::new()),
+            by_host: Mutex::new(HashMap::new()),
+            next_id: AtomicU64::new(1),
+        }
+    }
+
+    fn lookup(&self, id: &span::Id) -> Option<(span::Id, span::Id)> {
+        let spans = self.spans.lock().unwrap();
+        spans
+            .get(&id.into_u64())
+            .map(|ids| (ids.host.clone(), ids.plugin.clone()))
+    }
+}
+
+impl Collect for Broadcast {
+    fn register_callsite(&self, metadata: &'static Metadata<'static>) -> Interest {
+        let host = self.host.register_callsite(metadata);
+        let plugin = self.plugin.register_callsite(metadata);
+        if host.is_never() && plugin.is_never() {
+            Interest::never()
+        } else if host.is_always() && plugin.is_always() {
+            Interest::always()
+        } else {
+            // At least one of the two wants this callsite sometimes (or
+            // always, while the other never does); ask to be consulted via
+            // `enabled` on every occurrence rather than caching a verdict
+            // that would be wrong for the other dispatch.
+            Interest::sometimes()
+        }
+    }
+
+    fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+        self.host.enabled(metadata) || self.plugin.enabled(metadata)
+    }
+
+    // `Dispatch` doesn't expose `max_level_hint` publicly (only callsites
+    // registered through it can see that), so this falls back to the
+    // `Collect` trait's default of `None` -- no upper bound -- rather than
+    // risk telling a callsite to skip a level one of the two dispatches
+    // actually wants.
+
+    fn new_span(&self, attrs: &span::Attributes<'_>) -> span::Id {
+        let (host_id, plugin_id) = match attrs.parent() {
+            Some(parent) => {
+                let (host_parent, plugin_parent) = self
+                    .lookup(parent)
+                    .unwrap_or_else(|| (parent.clone(), parent.clone()));
+                let host_attrs =
+                    span::Attributes::child_of(host_parent, attrs.metadata(), attrs.values());
+                let plugin_attrs =
+                    span::Attributes::child_of(plugin_parent, attrs.metadata(), attrs.values());
+                (
+                    self.host.new_span(&host_attrs),
+                    self.plugin.new_span(&plugin_attrs),
+                )
+            }
+            // Contextual (resolved from each dispatch's own current span,
+            // which `enter`/`exit` below keep in step) and root spans both
+            // carry no explicit parent, so the original `attrs` is valid for
+            // both dispatches unchanged.
+            None => (self.host.new_span(attrs), self.plugin.new_span(attrs)),
+        };
+
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        self.by_host.lock().unwrap().insert(host_id.into_u64(), id);
+        self.spans.lock().unwrap().insert(
+            id,
+            SpanIds {
+                host: host_id,
+                plugin: plugin_id,
+                refs: AtomicU64::new(1),
+            },
+        );
+        span::Id::from_u64(id)
+    }
+
+    fn record(&self, id: &span::Id, values: &span::Record<'_>) {
+        if let Some((host_id, plugin_id)) = self.lookup(id) {
+            self.host.record(&host_id, values);
+            self.plugin.record(&plugin_id, values);
+        }
+    }
+
+    fn record_follows_from(&self, id: &span::Id, follows: &span::Id) {
+        if let (Some((host_id, plugin_id)), Some((host_follows, plugin_follows))) =
+            (self.lookup(id), self.lookup(follows))
+        {
+            self.host.record_follows_from(&host_id, &host_follows);
+            self.plugin.record_follows_from(&plugin_id, &plugin_follows);
+        }
+    }
+
+    fn event(&self, event: &Event<'_>) {
+        // See "Limitations" in the module docs: an explicit-parent event's
+        // parent id isn't translated, since `Event` doesn't expose its
+        // `ValueSet` publicly and so can't be reconstructed with a
+        // different one here.
+        self.host.event(event);
+        self.plugin.event(event);
+    }
+
+    fn enter(&self, id: &span::Id) {
+        if let Some((host_id, plugin_id)) = self.lookup(id) {
+            self.host.enter(&host_id);
+            self.plugin.enter(&plugin_id);
+        }
+    }
+
+    fn exit(&self, id: &span::Id) {
+        if let Some((host_id, plugin_id)) = self.lookup(id) {
+            self.plugin.exit(&plugin_id);
+            self.host.exit(&host_id);
+        }
+    }
+
+    fn clone_span(&self, id: &span::Id) -> span::Id {
+        let spans = self.spans.lock().unwrap();
+        if let Some(ids) = spans.get(&id.into_u64()) {
+            self.host.clone_span(&ids.host);
+            self.plugin.clone_span(&ids.plugin);
+            ids.refs.fetch_add(1, Ordering::Relaxed);
+        }
+        id.clone()
+    }
+
+    fn try_close(&self, id: span::Id) -> bool {
+        let key = id.into_u64();
+        let mut spans = self.spans.lock().unwrap();
+        let closed = match spans.get(&key) {
+            Some(ids) => {
+                self.host.try_close(ids.host.clone());
+                self.plugin.try_close(ids.plugin.clone());
+                ids.refs.fetch_sub(1, Ordering::Relaxed) == 1
+            }
+            None => false,
+        };
+
+        if closed {
+            if let Some(ids) = spans.remove(&key) {
+                self.by_host.lock().unwrap().remove(&ids.host.into_u64());
+            }
+        }
+
+        closed
+    }
+
+    fn current_span(&self) -> span::Current {
+        let current = self.host.current_span();
+        if !current.is_known() {
+            return span::Current::unknown();
+        }
+
+        match current.into_inner() {
+            None => span::Current::none(),
+            Some((host_id, metadata)) => {
+                match self.by_host.lock().unwrap().get(&host_id.into_u64()) {
+                    Some(&id) => span::Current::new(span::Id::from_u64(id), metadata),
+                    None => span::Current::none(),
+                }
+            }
+        }
+    }
+}
+
+#[cfg(all(test, feature = "registry"))]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex as StdMutex};
+    use tracing_core::dispatch::with_default;
+    use tracing_core::span::{Attributes, Record};
+
+    #[derive(Clone, Default)]
+    struct Recorder {
+        events: Arc<StdMutex<Vec<&'static str>>>,
+    }
+
+    impl Collect for Recorder {
+        fn register_callsite(&self, _: &'static Metadata<'static>) -> Interest {
+            Interest::always()
+        }
+        fn enabled(&self, _: &Metadata<'_>) -> bool {
+            true
+        }
+        fn new_span(&self, _: &Attributes<'_>) -> span::Id {
+            span::Id::from_u64(1)
+        }
+        fn record(&self, _: &span::Id, _: &Record<'_>) {}
+        fn record_follows_from(&self, _: &span::Id, _: &span::Id) {}
+        fn event(&self, event: &Event<'_>) {
+            self.events.lock().unwrap().push(event.metadata().name());
+        }
+        fn enter(&self, _: &span::Id) {}
+        fn exit(&self, _: &span::Id) {}
+        fn current_span(&self) -> span::Current {
+            span::Current::unknown()
+        }
+    }
+
+    #[test]
+    fn events_reach_both_the_host_and_the_plugin() {
+        let host = Recorder::default();
+        let plugin = Recorder::default();
+
+        let host_dispatch = Dispatch::new(host.clone());
+        with_default(&host_dispatch, || {
+            let _guard = attach(Dispatch::new(plugin.clone()));
+            tracing::info!("hello");
+        });
+
+        assert_eq!(host.events.lock().unwrap().len(), 1);
+        assert_eq!(plugin.events.lock().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn detaching_stops_the_plugin_from_seeing_further_events() {
+        let host = Recorder::default();
+        let plugin = Recorder::default();
+
+        let host_dispatch = Dispatch::new(host.clone());
+        with_default(&host_dispatch, || {
+            let guard = attach(Dispatch::new(plugin.clone()));
+            tracing::info!("seen by both");
+            drop(guard);
+            tracing::info!("seen by the host only");
+        });
+
+        assert_eq!(host.events.lock().unwrap().len(), 2);
+        assert_eq!(plugin.events.lock().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn nested_spans_translate_ids_for_both_dispatches() {
+        let host = crate::registry();
+        let plugin = crate::registry();
+
+        let host_dispatch = Dispatch::new(host);
+        with_default(&host_dispatch, || {
+            let _guard = attach(Dispatch::new(plugin));
+
+            let outer = tracing::info_span!("outer");
+            let _outer = outer.enter();
+            let inner = tracing::info_span!("inner");
+            let _inner = inner.enter();
+            tracing::info!("nested");
+        });
+    }
+}
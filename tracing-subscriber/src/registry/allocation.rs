@@ -0,0 +1,228 @@
+//! Per-span allocation accounting.
+//!
+//! [`AllocationSubscriber`] tracks, for each span, how many bytes and
+//! allocations were made by a global allocator wrapped in
+//! [`TrackingAllocator`] while that span was entered, and emits an event
+//! carrying the totals when the span closes.
+//!
+//! This module requires the "alloc-tracking" feature flag.
+use std::alloc::{GlobalAlloc, Layout};
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+
+use tracing_core::{span, Collect};
+
+use crate::registry::LookupSpan;
+use crate::subscribe::{Context, Subscribe};
+
+thread_local! {
+    // The stack of spans currently entered on this thread, innermost last,
+    // mirroring the collector's own span stack. `TrackingAllocator` consults
+    // only the top of this stack.
+    static SPAN_STACK: RefCell<Vec<span::Id>> = RefCell::new(Vec::new());
+    // Bytes/allocation counts accumulated so far for each span on the
+    // current thread's stack, keyed by the span's raw id.
+    static TOTALS: RefCell<HashMap<u64, AllocStats>> = RefCell::new(HashMap::new());
+    // Guards against re-entering the accounting path if recording an
+    // allocation itself allocates (e.g. the first insert into `TOTALS`).
+    static IN_ACCOUNTING: Cell<bool> = Cell::new(false);
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+struct AllocStats {
+    bytes: u64,
+    count: u64,
+}
+
+/// Returns the [`Id`] of the span that is currently considered "current" for
+/// allocation accounting purposes on this thread, if any.
+///
+/// [`Id`]: span::Id
+fn current_span_id() -> Option<span::Id> {
+    SPAN_STACK.with(|stack| stack.borrow().last().cloned())
+}
+
+fn record(bytes: usize, delta: i64) {
+    if IN_ACCOUNTING.with(Cell::get) {
+        return;
+    }
+    let id = match current_span_id() {
+        Some(id) => id,
+        None => return,
+    };
+    IN_ACCOUNTING.with(|guard| guard.set(true));
+    TOTALS.with(|totals| {
+        let mut totals = totals.borrow_mut();
+        let entry = totals.entry(id.into_u64()).or_default();
+        if delta > 0 {
+            entry.bytes = entry.bytes.saturating_add(bytes as u64);
+            entry.count = entry.count.saturating_add(1);
+        }
+    });
+    IN_ACCOUNTING.with(|guard| guard.set(false));
+}
+
+/// A [`GlobalAlloc`] wrapper that attributes allocation counts and byte
+/// totals to the currently-entered span, as tracked by
+/// [`AllocationSubscriber`].
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use tracing_subscriber::registry::allocation::TrackingAllocator;
+///
+/// #[global_allocator]
+/// static ALLOC: TrackingAllocator<std::alloc::System> =
+///     TrackingAllocator(std::alloc::System);
+/// ```
+#[derive(Debug)]
+pub struct TrackingAllocator<A>(pub A);
+
+unsafe impl<A: GlobalAlloc> GlobalAlloc for TrackingAllocator<A> {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        record(layout.size(), 1);
+        self.0.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        self.0.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        if new_size > layout.size() {
+            record(new_size - layout.size(), 1);
+        }
+        self.0.realloc(ptr, layout, new_size)
+    }
+}
+
+/// A [`Subscribe`] that maintains the per-thread span stack consulted by
+/// [`TrackingAllocator`], and emits a [`trace`]-level event recording the
+/// accumulated allocation totals each time a tracked span closes.
+///
+/// [`trace`]: tracing_core::Level::TRACE
+#[derive(Clone, Debug, Default)]
+pub struct AllocationSubscriber {
+    _p: (),
+}
+
+impl AllocationSubscriber {
+    /// Creates a new `AllocationSubscriber`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl<C> Subscribe<C> for AllocationSubscriber
+where
+    C: Collect + for<'a> LookupSpan<'a>,
+{
+    fn on_enter(&self, id: &span::Id, _cx: Context<'_, C>) {
+        // Pushing onto `SPAN_STACK` can itself allocate (e.g. growing the
+        // `Vec`), which would call back into `record` while the
+        // `borrow_mut` below is still live. Guard it the same way `record`
+        // guards its own reentry, so that inner call is a no-op instead of
+        // a double-borrow panic.
+        IN_ACCOUNTING.with(|guard| guard.set(true));
+        SPAN_STACK.with(|stack| stack.borrow_mut().push(id.clone()));
+        IN_ACCOUNTING.with(|guard| guard.set(false));
+    }
+
+    fn on_exit(&self, id: &span::Id, _cx: Context<'_, C>) {
+        SPAN_STACK.with(|stack| {
+            let mut stack = stack.borrow_mut();
+            if stack.last() == Some(id) {
+                stack.pop();
+            }
+        });
+    }
+
+    fn on_close(&self, id: span::Id, _cx: Context<'_, C>) {
+        let stats = TOTALS.with(|totals| totals.borrow_mut().remove(&id.into_u64()));
+        if let Some(stats) = stats {
+            tracing::trace!(
+                target: "tracing_subscriber::registry::allocation",
+                alloc_bytes = stats.bytes,
+                alloc_count = stats.count,
+                "span allocation totals"
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+    use std::alloc::System;
+    use std::sync::{Arc, Mutex};
+    use tracing_core::field::{Field, Visit};
+    use tracing_core::{Event, LevelFilter};
+
+    // Installing this as the process's global allocator is exactly what
+    // exercises the `on_enter` reentrancy bug: growing `SPAN_STACK` below
+    // calls back into `alloc`, which calls `record`, while `on_enter`'s own
+    // `borrow_mut` of `SPAN_STACK` is still on the stack.
+    #[global_allocator]
+    static ALLOC: TrackingAllocator<System> = TrackingAllocator(System);
+
+    #[derive(Clone, Default)]
+    struct CaptureTotals {
+        stats: Arc<Mutex<Option<(u64, u64)>>>,
+    }
+
+    struct RecordStats {
+        bytes: Option<u64>,
+        count: Option<u64>,
+    }
+
+    impl Visit for RecordStats {
+        fn record_u64(&mut self, field: &Field, value: u64) {
+            match field.name() {
+                "alloc_bytes" => self.bytes = Some(value),
+                "alloc_count" => self.count = Some(value),
+                _ => {}
+            }
+        }
+        fn record_debug(&mut self, _field: &Field, _value: &dyn std::fmt::Debug) {}
+    }
+
+    impl<C> Subscribe<C> for CaptureTotals
+    where
+        C: Collect + for<'a> LookupSpan<'a>,
+    {
+        fn on_event(&self, event: &Event<'_>, _cx: Context<'_, C>) {
+            let mut visitor = RecordStats {
+                bytes: None,
+                count: None,
+            };
+            event.record(&mut visitor);
+            if let (Some(bytes), Some(count)) = (visitor.bytes, visitor.count) {
+                *self.stats.lock().unwrap() = Some((bytes, count));
+            }
+        }
+    }
+
+    #[test]
+    fn allocating_while_entering_a_span_does_not_panic_and_is_still_counted() {
+        let capture = CaptureTotals::default();
+        let stats = capture.stats.clone();
+        let subscriber = crate::registry()
+            .with(LevelFilter::TRACE)
+            .with(AllocationSubscriber::new())
+            .with(capture);
+
+        tracing_core::dispatch::with_default(&tracing_core::Dispatch::new(subscriber), || {
+            let span = tracing::info_span!("alloc");
+            let _guard = span.enter();
+            // Force a real heap allocation while the span is entered, going
+            // through `ALLOC` above.
+            let v: Vec<u8> = vec![0u8; 1024];
+            std::hint::black_box(&v);
+        });
+
+        let (bytes, count) = stats.lock().unwrap().expect("totals were recorded");
+        assert!(bytes > 0);
+        assert!(count > 0);
+    }
+}
@@ -0,0 +1,253 @@
+//! Recording compile-time build metadata into every root span.
+//!
+//! [`RecordBuildInfo`] stamps a fixed [`BuildInfo`] -- crate version, build
+//! profile, and (if available) git SHA -- onto every root span's
+//! [`Extensions`], where [`BuildInfo::current`] can read it back from that
+//! span or any of its descendants. Exported traces are then attributable to
+//! the exact build that produced them, without each service wiring that
+//! attribution in by hand.
+//!
+//! This module requires the "build-info" feature flag.
+//!
+//! [`Extensions`]: crate::registry::Extensions
+use tracing_core::{span, Collect};
+
+use crate::registry::LookupSpan;
+use crate::subscribe::{Context, Subscribe};
+
+/// Captures the current crate version and build profile as a [`BuildInfo`],
+/// for use with [`RecordBuildInfo`].
+///
+/// The git SHA is only included if a `GIT_SHA` environment variable was set
+/// at compile time -- for example, by a build script that shells out to
+/// `git rev-parse HEAD` and forwards it with
+/// `println!("cargo:rustc-env=GIT_SHA={sha}")`. Without one, `git_sha` is
+/// `None`; set it explicitly with [`BuildInfoBuilder::git_sha`] instead if
+/// you have it some other way.
+///
+/// ```rust
+/// use tracing_subscriber::registry::build_info::RecordBuildInfo;
+///
+/// let _subscriber = RecordBuildInfo::new(tracing_subscriber::build_info!());
+/// ```
+///
+/// [`BuildInfoBuilder::git_sha`]: crate::registry::build_info::BuildInfoBuilder::git_sha
+#[macro_export]
+#[cfg(feature = "build-info")]
+macro_rules! build_info {
+    () => {
+        $crate::registry::build_info::BuildInfo {
+            crate_version: Some(env!("CARGO_PKG_VERSION")),
+            profile: Some(if cfg!(debug_assertions) {
+                "debug"
+            } else {
+                "release"
+            }),
+            git_sha: option_env!("GIT_SHA"),
+        }
+    };
+}
+
+/// Compile-time build metadata, as recorded onto every root span by
+/// [`RecordBuildInfo`].
+///
+/// Construct one with [`BuildInfo::builder`], or capture the current crate
+/// version and build profile automatically with the [`build_info!`] macro.
+///
+/// [`build_info!`]: crate::build_info!
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct BuildInfo {
+    /// The crate version the binary was built from, e.g. `CARGO_PKG_VERSION`.
+    pub crate_version: Option<&'static str>,
+    /// The build profile: `"debug"` or `"release"`.
+    pub profile: Option<&'static str>,
+    /// The git commit SHA the build was produced from, if the build captured
+    /// one (`tracing-subscriber` has no way to run `git` itself).
+    pub git_sha: Option<&'static str>,
+}
+
+impl BuildInfo {
+    /// Returns a new, empty [`BuildInfoBuilder`].
+    pub fn builder() -> BuildInfoBuilder {
+        BuildInfoBuilder::default()
+    }
+
+    /// Returns the [`BuildInfo`] recorded on `id`'s root span, if any
+    /// [`RecordBuildInfo`] subscriber has visited it.
+    pub fn current<C>(ctx: &Context<'_, C>, id: &span::Id) -> Option<Self>
+    where
+        C: Collect + for<'a> LookupSpan<'a>,
+    {
+        let span = ctx.span(id)?;
+        let root = span.scope().from_root().next()?;
+        let info = root.extensions().get::<Self>().copied();
+        info
+    }
+}
+
+/// Builds a [`BuildInfo`].
+#[derive(Clone, Copy, Debug, Default)]
+pub struct BuildInfoBuilder(BuildInfo);
+
+impl BuildInfoBuilder {
+    /// Sets the crate version, e.g. `env!("CARGO_PKG_VERSION")`.
+    pub fn crate_version(mut self, crate_version: &'static str) -> Self {
+        self.0.crate_version = Some(crate_version);
+        self
+    }
+
+    /// Sets the build profile, e.g. `"release"`.
+    pub fn profile(mut self, profile: &'static str) -> Self {
+        self.0.profile = Some(profile);
+        self
+    }
+
+    /// Sets the git commit SHA the build was produced from.
+    pub fn git_sha(mut self, git_sha: &'static str) -> Self {
+        self.0.git_sha = Some(git_sha);
+        self
+    }
+
+    /// Finishes building the [`BuildInfo`].
+    pub fn build(self) -> BuildInfo {
+        self.0
+    }
+}
+
+/// A [`Subscribe`] that stamps a fixed [`BuildInfo`] onto every root span's
+/// [`Extensions`], so it can be recovered from that span or any of its
+/// descendants with [`BuildInfo::current`].
+///
+/// If constructed with [`RecordBuildInfo::with_root_event`], it also emits a
+/// `tracing` event with `build_version`/`build_profile`/`build_git_sha`
+/// fields when each root span is created, for subscribers that only look at
+/// events (e.g. plain log shippers) rather than reading span extensions.
+///
+/// [`Extensions`]: crate::registry::Extensions
+#[derive(Clone, Copy, Debug)]
+pub struct RecordBuildInfo {
+    info: BuildInfo,
+    root_event: bool,
+}
+
+impl RecordBuildInfo {
+    /// Returns a new `RecordBuildInfo` that stamps `info` onto every root
+    /// span's extensions.
+    pub fn new(info: BuildInfo) -> Self {
+        Self {
+            info,
+            root_event: false,
+        }
+    }
+
+    /// Configures this `RecordBuildInfo` to also emit a `tracing` event,
+    /// carrying `info`'s fields, when each root span is created.
+    ///
+    /// This defaults to `false`.
+    pub fn with_root_event(self, root_event: bool) -> Self {
+        Self { root_event, ..self }
+    }
+}
+
+impl<C> Subscribe<C> for RecordBuildInfo
+where
+    C: Collect + for<'a> LookupSpan<'a>,
+{
+    fn new_span(&self, _attrs: &span::Attributes<'_>, id: &span::Id, ctx: Context<'_, C>) {
+        let span = ctx.span(id).expect("span must exist, this is a bug");
+        if span.parent().is_some() {
+            return;
+        }
+        span.extensions_mut().insert(self.info);
+        if self.root_event {
+            let version = self.info.crate_version.unwrap_or("unknown");
+            let profile = self.info.profile.unwrap_or("unknown");
+            let git_sha = self.info.git_sha.unwrap_or("unknown");
+            tracing::trace!(
+                target: "tracing_subscriber::registry::build_info",
+                build_version = version,
+                build_profile = profile,
+                build_git_sha = git_sha,
+                "build info"
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::registry::Registry;
+    use std::sync::{Arc, Mutex};
+    use tracing::collect::with_default;
+
+    #[test]
+    fn stamps_build_info_on_root_span_extensions_only() {
+        let info = BuildInfo::builder()
+            .crate_version("1.2.3")
+            .profile("release")
+            .git_sha("deadbeef")
+            .build();
+
+        with_default(
+            RecordBuildInfo::new(info).with_collector(Registry::default()),
+            || {
+                let root = tracing::info_span!("root");
+                let _root_enter = root.enter();
+                let child = tracing::info_span!("child");
+
+                tracing::dispatch::get_default(|dispatch| {
+                    let registry = dispatch.downcast_ref::<Registry>().unwrap();
+                    let root_span = registry.span(&root.id().unwrap()).unwrap();
+                    let child_span = registry.span(&child.id().unwrap()).unwrap();
+
+                    assert_eq!(
+                        root_span.extensions().get::<BuildInfo>().copied(),
+                        Some(info)
+                    );
+                    assert_eq!(
+                        child_span.extensions().get::<BuildInfo>().copied(),
+                        None,
+                        "build info should only be stamped on the root span itself"
+                    );
+                });
+            },
+        );
+    }
+
+    /// Captures the [`BuildInfo`] visible from a span just before it closes,
+    /// so the test can assert on it after `with_default` returns.
+    struct CaptureOnClose {
+        captured: Arc<Mutex<Option<BuildInfo>>>,
+    }
+
+    impl<C> Subscribe<C> for CaptureOnClose
+    where
+        C: Collect + for<'a> LookupSpan<'a>,
+    {
+        fn on_close(&self, id: span::Id, ctx: Context<'_, C>) {
+            *self.captured.lock().unwrap() = BuildInfo::current(&ctx, &id);
+        }
+    }
+
+    #[test]
+    fn current_looks_up_through_descendants() {
+        let info = BuildInfo::builder().crate_version("1.2.3").build();
+        let captured = Arc::new(Mutex::new(None));
+
+        let subscriber = RecordBuildInfo::new(info)
+            .and_then(CaptureOnClose {
+                captured: captured.clone(),
+            })
+            .with_collector(Registry::default());
+
+        with_default(subscriber, || {
+            let root = tracing::info_span!("root");
+            let _root_enter = root.enter();
+            let child = tracing::info_span!("child");
+            drop(child);
+        });
+
+        assert_eq!(*captured.lock().unwrap(), Some(info));
+    }
+}
@@ -0,0 +1,274 @@
+//! Retaining recent low-severity events per span, so a later `WARN`/`ERROR`
+//! can be reported alongside the context that led up to it.
+//!
+//! [`ErrorContext`] is a [`Subscribe`] that keeps a bounded, per-span ring
+//! buffer of the most recent events below `WARN`. Whenever an event at
+//! `WARN` or `ERROR` is recorded, `ErrorContext` always keeps it, and hands
+//! it to a sink together with the buffered events from the same span --
+//! giving "the error, plus what just happened in this span" as a single
+//! configurable component, rather than something every call site has to
+//! assemble by hand.
+//!
+//! This module requires the "error-context" feature flag.
+use std::collections::{BTreeMap, VecDeque};
+
+use tracing_core::{
+    field::{Field, Visit},
+    Collect, Event, Level,
+};
+
+use crate::registry::LookupSpan;
+use crate::subscribe::{Context, Subscribe};
+
+/// A single event retained in an [`ErrorContext`]'s per-span ring buffer, or
+/// passed to its sink as the event that triggered a flush.
+#[derive(Clone, Debug)]
+pub struct ContextEvent {
+    /// The event's target.
+    pub target: &'static str,
+    /// The event's verbosity level.
+    pub level: Level,
+    /// The event's fields, keyed by field name.
+    pub fields: BTreeMap<&'static str, String>,
+}
+
+/// A `WARN` or `ERROR` event, together with the lower-severity events
+/// recorded in the same span just before it, as passed to an
+/// [`ErrorContext`]'s sink.
+///
+/// [`context`](ErrorEvent::context) is ordered oldest first, ending with the
+/// event recorded immediately before [`trigger`](ErrorEvent::trigger).
+#[derive(Clone, Debug)]
+pub struct ErrorEvent {
+    /// The `WARN` or `ERROR` event that triggered this flush.
+    pub trigger: ContextEvent,
+    /// The buffered lower-severity events from the same span, if the event
+    /// was recorded inside one.
+    pub context: Vec<ContextEvent>,
+}
+
+/// Receives an [`ErrorEvent`] each time an [`ErrorContext`] flushes.
+///
+/// Implemented for any `Fn(ErrorEvent) + Send + Sync`, so a closure can be
+/// used directly; implement it on a named type to hold onto the flushed
+/// events (e.g. for forwarding to an error-reporting service).
+pub trait ErrorSink {
+    /// Called with the triggering event and its buffered context.
+    fn on_error(&self, error: ErrorEvent);
+}
+
+impl<F> ErrorSink for F
+where
+    F: Fn(ErrorEvent) + Send + Sync,
+{
+    fn on_error(&self, error: ErrorEvent) {
+        (self)(error)
+    }
+}
+
+struct RingBuffer(VecDeque<ContextEvent>);
+
+/// A [`Subscribe`] that always keeps `WARN` and `ERROR` events, and
+/// retroactively attaches the most recent lower-severity events from the
+/// same span as context.
+///
+/// # Examples
+///
+/// ```
+/// use std::sync::{Arc, Mutex};
+/// use tracing_subscriber::prelude::*;
+/// use tracing_subscriber::registry::error_context::{ErrorContext, ErrorEvent};
+///
+/// let flushed = Arc::new(Mutex::new(Vec::new()));
+/// let sink = flushed.clone();
+/// let error_context = ErrorContext::new(4, move |error: ErrorEvent| {
+///     sink.lock().unwrap().push(error);
+/// });
+/// let subscriber = tracing_subscriber::registry().with(error_context);
+///
+/// tracing::collect::with_default(subscriber, || {
+///     let _span = tracing::info_span!("request").entered();
+///     tracing::debug!(step = "connect", "connecting");
+///     tracing::debug!(step = "auth", "authenticating");
+///     tracing::error!(status = 500, "request failed");
+/// });
+///
+/// let flushed = flushed.lock().unwrap();
+/// assert_eq!(flushed.len(), 1);
+/// assert_eq!(flushed[0].trigger.fields.get("status").map(String::as_str), Some("500"));
+/// assert_eq!(flushed[0].context.len(), 2);
+/// assert_eq!(flushed[0].context[1].fields.get("step").map(String::as_str), Some("auth"));
+/// ```
+pub struct ErrorContext<K> {
+    capacity: usize,
+    sink: K,
+}
+
+impl<K> ErrorContext<K>
+where
+    K: ErrorSink,
+{
+    /// Returns a new `ErrorContext` that retains up to `capacity`
+    /// lower-severity events per span, and hands each `WARN`/`ERROR` event
+    /// (with its context) to `sink`.
+    pub fn new(capacity: usize, sink: K) -> Self {
+        Self { capacity, sink }
+    }
+}
+
+struct RecordedFields(BTreeMap<&'static str, String>);
+
+impl Visit for RecordedFields {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.0.insert(field.name(), value.to_string());
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        self.0.insert(field.name(), format!("{:?}", value));
+    }
+}
+
+impl<C, K> Subscribe<C> for ErrorContext<K>
+where
+    C: Collect + for<'a> LookupSpan<'a>,
+    K: ErrorSink + 'static,
+{
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, C>) {
+        let mut fields = RecordedFields(BTreeMap::new());
+        event.record(&mut fields);
+        let recorded = ContextEvent {
+            target: event.metadata().target(),
+            level: *event.metadata().level(),
+            fields: fields.0,
+        };
+
+        let span = ctx.event_span(event);
+
+        if recorded.level <= Level::WARN {
+            let context = span
+                .as_ref()
+                .and_then(|span| span.extensions().get::<RingBuffer>().map(|ring| ring.0.iter().cloned().collect()))
+                .unwrap_or_default();
+            self.sink.on_error(ErrorEvent {
+                trigger: recorded,
+                context,
+            });
+            return;
+        }
+
+        let span = match span {
+            Some(span) => span,
+            None => return,
+        };
+        let mut extensions = span.extensions_mut();
+        match extensions.get_mut::<RingBuffer>() {
+            Some(ring) => {
+                if ring.0.len() == self.capacity {
+                    ring.0.pop_front();
+                }
+                ring.0.push_back(recorded);
+            }
+            None => {
+                let mut ring = VecDeque::with_capacity(self.capacity);
+                ring.push_back(recorded);
+                extensions.insert(RingBuffer(ring));
+            }
+        }
+    }
+}
+
+impl<K> std::fmt::Debug for ErrorContext<K> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ErrorContext")
+            .field("capacity", &self.capacity)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn keeps_error_and_attaches_context() {
+        let flushed = Arc::new(Mutex::new(Vec::new()));
+        let sink = flushed.clone();
+        let error_context = ErrorContext::new(4, move |error: ErrorEvent| {
+            sink.lock().unwrap().push(error);
+        });
+        let subscriber = crate::registry().with(error_context);
+
+        tracing_core::dispatch::with_default(&tracing_core::Dispatch::new(subscriber), || {
+            let _span = tracing::info_span!("request").entered();
+            tracing::debug!(step = "connect", "connecting");
+            tracing::debug!(step = "auth", "authenticating");
+            tracing::error!(status = 500, "request failed");
+        });
+
+        let flushed = flushed.lock().unwrap();
+        assert_eq!(flushed.len(), 1);
+        assert_eq!(flushed[0].trigger.level, Level::ERROR);
+        assert_eq!(
+            flushed[0].trigger.fields.get("status").map(String::as_str),
+            Some("500")
+        );
+        assert_eq!(flushed[0].context.len(), 2);
+        assert_eq!(
+            flushed[0].context[0].fields.get("step").map(String::as_str),
+            Some("connect")
+        );
+        assert_eq!(
+            flushed[0].context[1].fields.get("step").map(String::as_str),
+            Some("auth")
+        );
+    }
+
+    #[test]
+    fn context_ring_buffer_is_capped() {
+        let flushed = Arc::new(Mutex::new(Vec::new()));
+        let sink = flushed.clone();
+        let error_context = ErrorContext::new(2, move |error: ErrorEvent| {
+            sink.lock().unwrap().push(error);
+        });
+        let subscriber = crate::registry().with(error_context);
+
+        tracing_core::dispatch::with_default(&tracing_core::Dispatch::new(subscriber), || {
+            let _span = tracing::info_span!("request").entered();
+            tracing::debug!(step = "one", "step");
+            tracing::debug!(step = "two", "step");
+            tracing::debug!(step = "three", "step");
+            tracing::warn!("uh oh");
+        });
+
+        let flushed = flushed.lock().unwrap();
+        assert_eq!(flushed[0].context.len(), 2);
+        assert_eq!(
+            flushed[0].context[0].fields.get("step").map(String::as_str),
+            Some("two")
+        );
+        assert_eq!(
+            flushed[0].context[1].fields.get("step").map(String::as_str),
+            Some("three")
+        );
+    }
+
+    #[test]
+    fn events_outside_a_span_have_no_context() {
+        let flushed = Arc::new(Mutex::new(Vec::new()));
+        let sink = flushed.clone();
+        let error_context = ErrorContext::new(4, move |error: ErrorEvent| {
+            sink.lock().unwrap().push(error);
+        });
+        let subscriber = crate::registry().with(error_context);
+
+        tracing_core::dispatch::with_default(&tracing_core::Dispatch::new(subscriber), || {
+            tracing::error!("no span here");
+        });
+
+        let flushed = flushed.lock().unwrap();
+        assert_eq!(flushed.len(), 1);
+        assert!(flushed[0].context.is_empty());
+    }
+}
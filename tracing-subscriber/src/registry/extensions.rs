@@ -2,14 +2,16 @@
 
 use crate::sync::{RwLockReadGuard, RwLockWriteGuard};
 use std::{
-    any::{Any, TypeId},
+    any::{self, Any, TypeId},
     collections::HashMap,
     fmt,
     hash::{BuildHasherDefault, Hasher},
+    mem,
 };
 
 #[allow(warnings)]
 type AnyMap = HashMap<TypeId, Box<dyn Any + Send + Sync>, BuildHasherDefault<IdHasher>>;
+type MetaMap = HashMap<TypeId, EntryMeta, BuildHasherDefault<IdHasher>>;
 
 /// With TypeIds as keys, there's no need to hash them. They are already hashes
 /// themselves, coming from the compiler. The IdHasher holds the u64 of
@@ -48,6 +50,20 @@ impl<'a> Extensions<'a> {
     pub fn get<T: 'static>(&self) -> Option<&T> {
         self.inner.get::<T>()
     }
+
+    /// Returns a diagnostic snapshot of every type currently stored in
+    /// these extensions, reporting each one's name and size in bytes.
+    ///
+    /// A stored type's value is only included (via [`ExtensionDebugInfo::value`])
+    /// if it was inserted with [`ExtensionsMut::insert_debug`] rather than
+    /// [`ExtensionsMut::insert`]. This is meant to help diagnose disagreements
+    /// between subscribers about a span's extensions -- for example, one
+    /// subscriber expecting another to have already inserted a particular
+    /// type -- without requiring every extension type to implement `Debug`,
+    /// or risking that its contents leak into diagnostics by default.
+    pub fn debug_entries(&self) -> Vec<ExtensionDebugInfo> {
+        self.inner.debug_entries()
+    }
 }
 
 /// An mutable reference to a Span's extensions.
@@ -93,6 +109,23 @@ impl<'a> ExtensionsMut<'a> {
         self.inner.insert(val)
     }
 
+    /// Like [`insert`](Self::insert), but also records `val`'s [`Debug`]
+    /// rendering, so it's included in [`Extensions::debug_entries`] instead
+    /// of just the type's name and size.
+    ///
+    /// ## Panics
+    ///
+    /// If `T` is already present in `Extensions`, then this method will panic.
+    pub fn insert_debug<T: fmt::Debug + Send + Sync + 'static>(&mut self, val: T) {
+        assert!(self.replace_debug(val).is_none())
+    }
+
+    /// Like [`replace`](Self::replace), but also records `val`'s [`Debug`]
+    /// rendering (see [`insert_debug`](Self::insert_debug)).
+    pub fn replace_debug<T: fmt::Debug + Send + Sync + 'static>(&mut self, val: T) -> Option<T> {
+        self.inner.insert_debug(val)
+    }
+
     /// Get a mutable reference to a type previously inserted on this `ExtensionsMut`.
     pub fn get_mut<T: 'static>(&mut self) -> Option<&mut T> {
         self.inner.get_mut::<T>()
@@ -114,6 +147,44 @@ impl<'a> ExtensionsMut<'a> {
 #[derive(Default)]
 pub(crate) struct ExtensionsInner {
     map: AnyMap,
+    meta: MetaMap,
+}
+
+/// Per-type bookkeeping used to answer [`ExtensionDebugInfo`] queries
+/// without requiring every extension type to implement [`Debug`](fmt::Debug).
+struct EntryMeta {
+    type_name: &'static str,
+    size: usize,
+    render: Option<fn(&(dyn Any + Send + Sync)) -> String>,
+}
+
+/// One entry in the diagnostic snapshot returned by
+/// [`Extensions::debug_entries`] and [`SpanRef::debug_extensions`].
+///
+/// [`SpanRef::debug_extensions`]: crate::registry::SpanRef::debug_extensions
+#[derive(Debug)]
+pub struct ExtensionDebugInfo {
+    type_name: &'static str,
+    size: usize,
+    value: Option<String>,
+}
+
+impl ExtensionDebugInfo {
+    /// The stored type's name, as returned by [`std::any::type_name`].
+    pub fn type_name(&self) -> &'static str {
+        self.type_name
+    }
+
+    /// The size, in bytes, of the stored value.
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// The value's `Debug` rendering, if it was inserted with
+    /// [`ExtensionsMut::insert_debug`]; otherwise `None`.
+    pub fn value(&self) -> Option<&str> {
+        self.value.as_deref()
+    }
 }
 
 impl ExtensionsInner {
@@ -122,6 +193,7 @@ impl ExtensionsInner {
     pub(crate) fn new() -> ExtensionsInner {
         ExtensionsInner {
             map: AnyMap::default(),
+            meta: MetaMap::default(),
         }
     }
 
@@ -130,6 +202,39 @@ impl ExtensionsInner {
     /// If a extension of this type already existed, it will
     /// be returned.
     pub(crate) fn insert<T: Send + Sync + 'static>(&mut self, val: T) -> Option<T> {
+        self.insert_with_render(val, None)
+    }
+
+    /// Like [`insert`](Self::insert), but also records a [`Debug`](fmt::Debug)
+    /// rendering function for the stored value.
+    pub(crate) fn insert_debug<T: fmt::Debug + Send + Sync + 'static>(
+        &mut self,
+        val: T,
+    ) -> Option<T> {
+        self.insert_with_render(
+            val,
+            Some(|any| {
+                let value = any
+                    .downcast_ref::<T>()
+                    .expect("type mismatch in ExtensionDebugInfo render");
+                format!("{:?}", value)
+            }),
+        )
+    }
+
+    fn insert_with_render<T: Send + Sync + 'static>(
+        &mut self,
+        val: T,
+        render: Option<fn(&(dyn Any + Send + Sync)) -> String>,
+    ) -> Option<T> {
+        self.meta.insert(
+            TypeId::of::<T>(),
+            EntryMeta {
+                type_name: any::type_name::<T>(),
+                size: mem::size_of::<T>(),
+                render,
+            },
+        );
         self.map
             .insert(TypeId::of::<T>(), Box::new(val))
             .and_then(|boxed| {
@@ -143,6 +248,25 @@ impl ExtensionsInner {
             })
     }
 
+    /// Returns a diagnostic snapshot of every type currently stored, per
+    /// [`Extensions::debug_entries`].
+    pub(crate) fn debug_entries(&self) -> Vec<ExtensionDebugInfo> {
+        self.meta
+            .iter()
+            .map(|(type_id, meta)| {
+                let value = meta
+                    .render
+                    .zip(self.map.get(type_id))
+                    .map(|(render, boxed)| render(&**boxed));
+                ExtensionDebugInfo {
+                    type_name: meta.type_name,
+                    size: meta.size,
+                    value,
+                }
+            })
+            .collect()
+    }
+
     /// Get a reference to a type previously inserted on this `Extensions`.
     pub(crate) fn get<T: 'static>(&self) -> Option<&T> {
         self.map
@@ -161,6 +285,7 @@ impl ExtensionsInner {
     ///
     /// If a extension of this type existed, it will be returned.
     pub(crate) fn remove<T: Send + Sync + 'static>(&mut self) -> Option<T> {
+        self.meta.remove(&TypeId::of::<T>());
         self.map.remove(&TypeId::of::<T>()).and_then(|boxed| {
             #[allow(warnings)]
             {
@@ -179,6 +304,7 @@ impl ExtensionsInner {
     /// that future spans will not need to allocate new hashmaps.
     pub(crate) fn clear(&mut self) {
         self.map.clear();
+        self.meta.clear();
     }
 }
 
@@ -215,6 +341,30 @@ mod tests {
         assert_eq!(extensions.get(), Some(&MyType(10)));
     }
 
+    #[test]
+    fn debug_entries_reports_type_names_and_opt_in_values() {
+        let mut extensions = ExtensionsInner::new();
+        extensions.insert(5i32);
+        extensions.insert_debug(MyType(10));
+
+        let entries = extensions.debug_entries();
+        assert_eq!(entries.len(), 2);
+
+        let plain = entries
+            .iter()
+            .find(|e| e.type_name() == any::type_name::<i32>())
+            .expect("i32 entry should be present");
+        assert_eq!(plain.size(), mem::size_of::<i32>());
+        assert_eq!(plain.value(), None);
+
+        let debuggable = entries
+            .iter()
+            .find(|e| e.type_name() == any::type_name::<MyType>())
+            .expect("MyType entry should be present");
+        assert_eq!(debuggable.size(), mem::size_of::<MyType>());
+        assert_eq!(debuggable.value(), Some("MyType(10)"));
+    }
+
     #[test]
     fn clear_retains_capacity() {
         let mut extensions = ExtensionsInner::new();
@@ -0,0 +1,164 @@
+//! Recording and exporting the span parent/`follows_from` graph.
+//!
+//! [`SpanGraph`] is a [`Subscribe`] that records, for a bounded window of the
+//! most recently closed spans, their name, parent, and `follows_from`
+//! relationships, and can render the recorded graph as a Graphviz DOT
+//! document on demand. This is intended as a debugging aid for diagnosing
+//! unexpected span parentage, particularly in asynchronous code where a span
+//! is not always entered by the task that eventually closes it.
+//!
+//! This module requires the "span-graph" feature flag.
+use std::collections::VecDeque;
+use std::fmt::Write;
+
+use tracing_core::{span, Collect};
+
+use crate::registry::LookupSpan;
+use crate::subscribe::{Context, Subscribe};
+use crate::sync::RwLock;
+
+/// A recorded span: its id, name, parent (if any), and any spans it
+/// `follows_from`.
+#[derive(Clone, Debug)]
+struct Node {
+    id: u64,
+    name: &'static str,
+    parent: Option<u64>,
+    follows_from: Vec<u64>,
+}
+
+/// A [`Subscribe`] that records span parent/`follows_from` relationships for
+/// a bounded window and can emit them as a Graphviz DOT graph.
+///
+/// # Examples
+///
+/// ```
+/// use tracing_subscriber::registry::graph::SpanGraph;
+/// use tracing_subscriber::prelude::*;
+///
+/// let graph = SpanGraph::new(1024);
+/// let subscriber = tracing_subscriber::registry().with(graph.clone());
+///
+/// tracing::collect::with_default(subscriber, || {
+///     let _span = tracing::info_span!("request").entered();
+/// });
+///
+/// println!("{}", graph.to_dot());
+/// ```
+#[derive(Clone, Debug)]
+pub struct SpanGraph {
+    nodes: std::sync::Arc<RwLock<VecDeque<Node>>>,
+    capacity: usize,
+}
+
+impl SpanGraph {
+    /// Returns a new `SpanGraph` that retains the most recently closed
+    /// `capacity` spans.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            nodes: std::sync::Arc::new(RwLock::new(VecDeque::with_capacity(capacity))),
+            capacity,
+        }
+    }
+
+    fn push(&self, node: Node) {
+        let mut nodes = try_lock!(self.nodes.write(), else return);
+        if nodes.len() == self.capacity {
+            nodes.pop_front();
+        }
+        nodes.push_back(node);
+    }
+
+    /// Renders the currently recorded span graph as a Graphviz DOT document.
+    pub fn to_dot(&self) -> String {
+        let nodes = try_lock!(self.nodes.read(), else return String::new());
+        let mut dot = String::from("digraph spans {\n");
+        for node in nodes.iter() {
+            let _ = writeln!(dot, "    {} [label=\"{}\"];", node.id, node.name);
+            if let Some(parent) = node.parent {
+                let _ = writeln!(dot, "    {} -> {};", parent, node.id);
+            }
+            for follows in &node.follows_from {
+                let _ = writeln!(
+                    dot,
+                    "    {} -> {} [style=dashed, label=\"follows_from\"];",
+                    follows, node.id
+                );
+            }
+        }
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+impl<C> Subscribe<C> for SpanGraph
+where
+    C: Collect + for<'a> LookupSpan<'a>,
+{
+    fn new_span(&self, attrs: &span::Attributes<'_>, id: &span::Id, ctx: Context<'_, C>) {
+        let parent = attrs
+            .parent()
+            .cloned()
+            .or_else(|| {
+                if attrs.is_contextual() {
+                    ctx.lookup_current().map(|span| span.id())
+                } else {
+                    None
+                }
+            })
+            .map(|id| id.into_u64());
+
+        let span = match ctx.span(id) {
+            Some(span) => span,
+            None => return,
+        };
+        self.push(Node {
+            id: id.into_u64(),
+            name: span.name(),
+            parent,
+            follows_from: Vec::new(),
+        });
+    }
+
+    fn on_follows_from(&self, span: &span::Id, follows: &span::Id, _ctx: Context<'_, C>) {
+        let mut nodes = try_lock!(self.nodes.write(), else return);
+        if let Some(node) = nodes.iter_mut().find(|node| node.id == span.into_u64()) {
+            node.follows_from.push(follows.into_u64());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+
+    #[test]
+    fn records_parent_edges() {
+        let graph = SpanGraph::new(8);
+        let subscriber = crate::registry().with(graph.clone());
+
+        tracing_core::dispatch::with_default(&tracing_core::Dispatch::new(subscriber), || {
+            let parent = tracing::info_span!("parent").entered();
+            let _child = tracing::info_span!("child").entered();
+            drop(parent);
+        });
+
+        let dot = graph.to_dot();
+        assert!(dot.contains("->"), "expected at least one edge, got: {}", dot);
+    }
+
+    #[test]
+    fn bounds_to_capacity() {
+        let graph = SpanGraph::new(2);
+        let subscriber = crate::registry().with(graph.clone());
+
+        tracing_core::dispatch::with_default(&tracing_core::Dispatch::new(subscriber), || {
+            for _ in 0..5 {
+                let _span = tracing::info_span!("span").entered();
+            }
+        });
+
+        assert_eq!(try_lock!(graph.nodes.read()).len(), 2);
+    }
+}
@@ -0,0 +1,183 @@
+//! Retaining a bounded history of recently-closed spans for post-hoc
+//! inspection.
+//!
+//! [`SpanHistory`] is a [`Subscribe`] that, when a span closes, copies its
+//! name, target, level, and recorded fields into a ring buffer capped at a
+//! fixed capacity. This lets an error handler (a panic hook, an
+//! [`on_event`][Subscribe::on_event] that reacts to an `ERROR` event, ...)
+//! call [`SpanHistory::recent`] to see what just finished -- for example, the
+//! SQL query span that completed right before a panic -- without paying the
+//! cost of retaining every span for the life of the process.
+//!
+//! This module requires the "span-history" feature flag.
+use std::collections::{BTreeMap, VecDeque};
+
+use tracing_core::{
+    field::{Field, Visit},
+    span, Collect, Level,
+};
+
+use crate::registry::LookupSpan;
+use crate::subscribe::{Context, Subscribe};
+use crate::sync::RwLock;
+
+/// A span captured in a [`SpanHistory`]'s ring buffer just after it closed.
+#[derive(Clone, Debug)]
+pub struct ClosedSpan {
+    /// The span's name.
+    pub name: &'static str,
+    /// The span's target.
+    pub target: &'static str,
+    /// The span's level.
+    pub level: Level,
+    /// The fields recorded on the span over its lifetime (from its
+    /// creation and any subsequent `record` calls), keyed by field name.
+    pub fields: BTreeMap<&'static str, String>,
+}
+
+/// A [`Subscribe`] that retains a bounded ring buffer of the most recently
+/// closed spans.
+///
+/// # Examples
+///
+/// ```
+/// use tracing_subscriber::registry::history::SpanHistory;
+/// use tracing_subscriber::prelude::*;
+///
+/// let history = SpanHistory::new(16);
+/// let subscriber = tracing_subscriber::registry().with(history.clone());
+///
+/// tracing::collect::with_default(subscriber, || {
+///     let _span = tracing::info_span!("query", sql = "SELECT 1").entered();
+/// });
+///
+/// assert_eq!(history.recent()[0].name, "query");
+/// ```
+#[derive(Clone, Debug)]
+pub struct SpanHistory {
+    spans: std::sync::Arc<RwLock<VecDeque<ClosedSpan>>>,
+    capacity: usize,
+}
+
+impl SpanHistory {
+    /// Returns a new `SpanHistory` that retains the most recently closed
+    /// `capacity` spans.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            spans: std::sync::Arc::new(RwLock::new(VecDeque::with_capacity(capacity))),
+            capacity,
+        }
+    }
+
+    /// Returns a snapshot of the currently retained closed spans, ordered
+    /// from oldest to most recently closed.
+    pub fn recent(&self) -> Vec<ClosedSpan> {
+        let spans = try_lock!(self.spans.read(), else return Vec::new());
+        spans.iter().cloned().collect()
+    }
+
+    fn push(&self, span: ClosedSpan) {
+        let mut spans = try_lock!(self.spans.write(), else return);
+        if spans.len() == self.capacity {
+            spans.pop_front();
+        }
+        spans.push_back(span);
+    }
+}
+
+struct RecordedFields(BTreeMap<&'static str, String>);
+
+impl Visit for RecordedFields {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.0.insert(field.name(), value.to_string());
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        self.0.insert(field.name(), format!("{:?}", value));
+    }
+}
+
+impl<C> Subscribe<C> for SpanHistory
+where
+    C: Collect + for<'a> LookupSpan<'a>,
+{
+    fn new_span(&self, attrs: &span::Attributes<'_>, id: &span::Id, ctx: Context<'_, C>) {
+        let span = match ctx.span(id) {
+            Some(span) => span,
+            None => return,
+        };
+        let mut fields = RecordedFields(BTreeMap::new());
+        attrs.record(&mut fields);
+        span.extensions_mut().insert(fields);
+    }
+
+    fn on_record(&self, id: &span::Id, values: &span::Record<'_>, ctx: Context<'_, C>) {
+        let span = match ctx.span(id) {
+            Some(span) => span,
+            None => return,
+        };
+        let mut extensions = span.extensions_mut();
+        if let Some(fields) = extensions.get_mut::<RecordedFields>() {
+            values.record(fields);
+        }
+    }
+
+    fn on_close(&self, id: span::Id, ctx: Context<'_, C>) {
+        let span = match ctx.span(&id) {
+            Some(span) => span,
+            None => return,
+        };
+        let metadata = span.metadata();
+        let fields = span
+            .extensions()
+            .get::<RecordedFields>()
+            .map(|fields| fields.0.clone())
+            .unwrap_or_default();
+
+        self.push(ClosedSpan {
+            name: metadata.name(),
+            target: metadata.target(),
+            level: *metadata.level(),
+            fields,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+
+    #[test]
+    fn records_fields_and_caps_capacity() {
+        let history = SpanHistory::new(2);
+        let subscriber = crate::registry().with(history.clone());
+
+        tracing_core::dispatch::with_default(&tracing_core::Dispatch::new(subscriber), || {
+            tracing::info_span!("first", sql = "SELECT 1").in_scope(|| {});
+            tracing::info_span!("second", sql = "SELECT 2").in_scope(|| {});
+            tracing::info_span!("third", sql = "SELECT 3").in_scope(|| {});
+        });
+
+        let recent = history.recent();
+        assert_eq!(recent.len(), 2);
+        assert_eq!(recent[0].name, "second");
+        assert_eq!(recent[1].name, "third");
+        assert_eq!(recent[1].fields.get("sql").map(String::as_str), Some("SELECT 3"));
+    }
+
+    #[test]
+    fn records_values_added_after_creation() {
+        let history = SpanHistory::new(4);
+        let subscriber = crate::registry().with(history.clone());
+
+        tracing_core::dispatch::with_default(&tracing_core::Dispatch::new(subscriber), || {
+            let span = tracing::info_span!("query", sql = tracing::field::Empty);
+            span.record("sql", &"SELECT 2");
+            span.in_scope(|| {});
+        });
+
+        let recent = history.recent();
+        assert_eq!(recent[0].fields.get("sql").map(String::as_str), Some("SELECT 2"));
+    }
+}
@@ -0,0 +1,198 @@
+//! Attaching cheap process resource metrics to high-severity events.
+//!
+//! [`HostMetricsLayer`] keeps a cached [`HostMetrics`] snapshot (RSS, open
+//! file descriptor count, and CPU time), refreshed at most once per sampling
+//! interval, and emits a companion event carrying it alongside every event
+//! at or above a configurable severity (`ERROR` by default). This gives
+//! incident logs the resource context needed for diagnosis -- was the
+//! process out of memory, leaking file descriptors, pegging a CPU -- without
+//! a separate metrics agent to correlate by hand.
+//!
+//! Sampling is currently only implemented on Linux, via `/proc/self`; on
+//! other platforms every field is always `None`.
+//!
+//! This module requires the "host-metrics" feature flag.
+use std::time::{Duration, Instant};
+
+use tracing_core::{Collect, Event, Level};
+
+use crate::registry::LookupSpan;
+use crate::subscribe::{Context, Subscribe};
+use crate::sync::RwLock;
+
+/// The target used for the companion event [`HostMetricsLayer`] emits, so it
+/// can be recognized (and exempted from anything that would otherwise treat
+/// it like the event it accompanies).
+const METRICS_TARGET: &str = "tracing_subscriber::registry::host_metrics";
+
+/// A snapshot of cheap process resource metrics, as attached by
+/// [`HostMetricsLayer`].
+///
+/// Every field is `None` if it couldn't be read on the current platform.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct HostMetrics {
+    /// Resident set size, in bytes.
+    pub rss_bytes: Option<u64>,
+    /// The number of open file descriptors.
+    pub open_fds: Option<u64>,
+    /// Total CPU time (user + system) consumed by the process so far.
+    pub cpu_time: Option<Duration>,
+}
+
+impl HostMetrics {
+    #[cfg(target_os = "linux")]
+    fn sample() -> Self {
+        Self {
+            rss_bytes: linux::rss_bytes(),
+            open_fds: linux::open_fds(),
+            cpu_time: linux::cpu_time(),
+        }
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    fn sample() -> Self {
+        Self::default()
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod linux {
+    use std::fs;
+    use std::time::Duration;
+
+    pub(super) fn rss_bytes() -> Option<u64> {
+        let status = fs::read_to_string("/proc/self/status").ok()?;
+        let line = status.lines().find(|line| line.starts_with("VmRSS:"))?;
+        let kb: u64 = line.split_whitespace().nth(1)?.parse().ok()?;
+        Some(kb * 1024)
+    }
+
+    pub(super) fn open_fds() -> Option<u64> {
+        Some(fs::read_dir("/proc/self/fd").ok()?.count() as u64)
+    }
+
+    pub(super) fn cpu_time() -> Option<Duration> {
+        let stat = fs::read_to_string("/proc/self/stat").ok()?;
+        // Field 2 (`comm`) is parenthesized and may itself contain spaces, so
+        // split on the closing paren and count remaining fields from there.
+        let after_comm = stat.rsplit_once(')')?.1;
+        let mut fields = after_comm.split_whitespace();
+        // Fields 14 and 15 (utime, stime) are the first two after `comm`'s
+        // closing paren and the state field.
+        let utime: u64 = fields.nth(11)?.parse().ok()?;
+        let stime: u64 = fields.next()?.parse().ok()?;
+        let ticks_per_sec = 100; // `sysconf(_SC_CLK_TCK)`, 100 on all common Linux configurations
+        Some(Duration::from_millis((utime + stime) * 1000 / ticks_per_sec))
+    }
+}
+
+struct Cached {
+    at: Instant,
+    metrics: HostMetrics,
+}
+
+/// A [`Subscribe`] that attaches a recent [`HostMetrics`] snapshot to every
+/// event at or above a configurable severity, by emitting a companion event
+/// carrying the snapshot's fields.
+///
+/// # Examples
+///
+/// ```
+/// use tracing_subscriber::prelude::*;
+/// use tracing_subscriber::registry::host_metrics::HostMetricsLayer;
+///
+/// let subscriber = tracing_subscriber::registry().with(HostMetricsLayer::new());
+/// tracing::collect::with_default(subscriber, || {
+///     tracing::error!("database connection lost");
+/// });
+/// ```
+pub struct HostMetricsLayer {
+    level: Level,
+    interval: Duration,
+    cache: RwLock<Cached>,
+}
+
+impl HostMetricsLayer {
+    /// Returns a new `HostMetricsLayer` that attaches metrics sampled at
+    /// most once per second to `ERROR` events.
+    pub fn new() -> Self {
+        Self {
+            level: Level::ERROR,
+            interval: Duration::from_secs(1),
+            cache: RwLock::new(Cached {
+                at: Instant::now(),
+                metrics: HostMetrics::sample(),
+            }),
+        }
+    }
+
+    /// Sets the minimum severity an event must have for metrics to be
+    /// attached to it.
+    ///
+    /// Defaults to [`Level::ERROR`].
+    pub fn with_level(self, level: Level) -> Self {
+        Self { level, ..self }
+    }
+
+    /// Sets how often the metrics snapshot is refreshed.
+    ///
+    /// Sampling `/proc` on every matching event would add needless overhead
+    /// to a hot error path; instead, a cached snapshot is reused until it's
+    /// older than `interval`. Defaults to one second.
+    pub fn with_interval(self, interval: Duration) -> Self {
+        Self { interval, ..self }
+    }
+
+    fn current(&self) -> HostMetrics {
+        {
+            let cached = try_lock!(self.cache.read(), else return HostMetrics::sample());
+            if cached.at.elapsed() < self.interval {
+                return cached.metrics;
+            }
+        }
+
+        let metrics = HostMetrics::sample();
+        let mut cached = try_lock!(self.cache.write(), else return metrics);
+        cached.at = Instant::now();
+        cached.metrics = metrics;
+        metrics
+    }
+}
+
+impl Default for HostMetricsLayer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl std::fmt::Debug for HostMetricsLayer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("HostMetricsLayer")
+            .field("level", &self.level)
+            .field("interval", &self.interval)
+            .finish()
+    }
+}
+
+impl<C> Subscribe<C> for HostMetricsLayer
+where
+    C: Collect + for<'a> LookupSpan<'a>,
+{
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, C>) {
+        if event.metadata().target() == METRICS_TARGET || event.metadata().level() > &self.level {
+            return;
+        }
+
+        let metrics = self.current();
+        // `-1` marks a metric that couldn't be sampled on this platform;
+        // there's no meaningful `Value` impl for `Option<u64>` to fall back
+        // on instead.
+        tracing::error!(
+            target: METRICS_TARGET,
+            rss_bytes = metrics.rss_bytes.map(|v| v as i64).unwrap_or(-1),
+            open_fds = metrics.open_fds.map(|v| v as i64).unwrap_or(-1),
+            cpu_time_ms = metrics.cpu_time.map(|d| d.as_millis() as i64).unwrap_or(-1),
+            "host metrics"
+        );
+    }
+}
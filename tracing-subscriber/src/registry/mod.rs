@@ -58,12 +58,40 @@
 //! [`Collect`]: tracing_core::collect::Collect
 //! [ctx]: crate::subscribe::Context
 //! [lookup]: crate::subscribe::Context::span()
+use std::borrow::Cow;
 use std::fmt::Debug;
 
 use tracing_core::{field::FieldSet, span::Id, Metadata};
 
 /// A module containing a type map of span extensions.
 mod extensions;
+#[cfg(feature = "alloc-tracking")]
+#[cfg_attr(docsrs, doc(cfg(feature = "alloc-tracking")))]
+pub mod allocation;
+#[cfg(feature = "build-info")]
+#[cfg_attr(docsrs, doc(cfg(feature = "build-info")))]
+pub mod build_info;
+#[cfg(feature = "error-context")]
+#[cfg_attr(docsrs, doc(cfg(feature = "error-context")))]
+pub mod error_context;
+#[cfg(feature = "host-metrics")]
+#[cfg_attr(docsrs, doc(cfg(feature = "host-metrics")))]
+pub mod host_metrics;
+#[cfg(feature = "span-capture")]
+#[cfg_attr(docsrs, doc(cfg(feature = "span-capture")))]
+pub mod span_capture;
+#[cfg(feature = "span-graph")]
+#[cfg_attr(docsrs, doc(cfg(feature = "span-graph")))]
+pub mod graph;
+#[cfg(feature = "span-history")]
+#[cfg_attr(docsrs, doc(cfg(feature = "span-history")))]
+pub mod history;
+#[cfg(feature = "span-timing")]
+#[cfg_attr(docsrs, doc(cfg(feature = "span-timing")))]
+pub mod timing;
+#[cfg(feature = "trace-context")]
+#[cfg_attr(docsrs, doc(cfg(feature = "trace-context")))]
+pub mod trace_context;
 
 cfg_feature!("registry", {
     mod sharded;
@@ -73,7 +101,7 @@ cfg_feature!("registry", {
     pub use sharded::Registry;
 });
 
-pub use extensions::{Extensions, ExtensionsMut};
+pub use extensions::{ExtensionDebugInfo, Extensions, ExtensionsMut};
 
 /// Provides access to stored span data.
 ///
@@ -166,16 +194,38 @@ pub struct SpanRef<'a, R: LookupSpan<'a>> {
 /// An iterator over the parents of a span, ordered from leaf to root.
 ///
 /// This is returned by the [`SpanRef::scope`] method.
+///
+/// Unlike [`Scope::from_root`], iterating over a `Scope` does not allocate:
+/// each call to [`next`] simply follows the current span's parent pointer
+/// into the registry, so traversal cost is `O(depth)` with no heap
+/// allocation regardless of how deep the span tree is.
+///
+/// [`next`]: Scope::next
 #[derive(Debug)]
 pub struct Scope<'a, R> {
     registry: &'a R,
     next: Option<Id>,
+    #[cfg(feature = "registry")]
+    filter: Option<crate::filter::layer_filters::FilterId>,
 }
 
 impl<'a, R> Scope<'a, R>
 where
     R: LookupSpan<'a>,
 {
+    /// Skips spans in this scope that the [`Filter`] identified by
+    /// `filter_id` did not enable, rather than returning every span in the
+    /// registry regardless of whether that filter's subscriber ever saw it.
+    ///
+    /// [`Filter`]: crate::filter::Filter
+    #[cfg(feature = "registry")]
+    pub(crate) fn with_filter(self, filter_id: crate::filter::layer_filters::FilterId) -> Self {
+        Self {
+            filter: Some(filter_id),
+            ..self
+        }
+    }
+
     /// Flips the order of the iterator, so that it is ordered from root to leaf.
     ///
     /// The iterator will first return the root span, then that span's immediate child,
@@ -205,9 +255,26 @@ where
     type Item = SpanRef<'a, R>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        let curr = self.registry.span(self.next.as_ref()?)?;
-        self.next = curr.parent_id().cloned();
-        Some(curr)
+        loop {
+            let curr = self.registry.span(self.next.as_ref()?)?;
+            self.next = curr.parent_id().cloned();
+
+            #[cfg(feature = "registry")]
+            {
+                if let Some(filter_id) = self.filter {
+                    let enabled = curr
+                        .extensions()
+                        .get::<crate::filter::layer_filters::FilterMap>()
+                        .map(|map| map.is_enabled(filter_id))
+                        .unwrap_or(true);
+                    if !enabled {
+                        continue;
+                    }
+                }
+            }
+
+            return Some(curr);
+        }
     }
 }
 
@@ -367,6 +434,8 @@ where
         Scope {
             registry: self.registry,
             next: Some(self.id()),
+            #[cfg(feature = "registry")]
+            filter: None,
         }
     }
 
@@ -385,6 +454,108 @@ where
     pub fn extensions_mut(&self) -> ExtensionsMut<'_> {
         self.data.extensions_mut()
     }
+
+    /// Returns a diagnostic snapshot of this span's [extensions](Extensions),
+    /// reporting each stored type's name and size in bytes, without exposing
+    /// its contents unless it was inserted with [`ExtensionsMut::insert_debug`].
+    ///
+    /// This is meant for diagnosing disagreements between subscribers about
+    /// what a span's extensions contain -- for example, one subscriber
+    /// expecting another to have already inserted a particular type -- since
+    /// today that kind of bug is mostly guesswork.
+    pub fn debug_extensions(&self) -> Vec<ExtensionDebugInfo> {
+        self.extensions().debug_entries()
+    }
+
+    /// Overrides the name this span reports to formatters and other
+    /// consumers, without changing its underlying [`Metadata`].
+    ///
+    /// This is useful when the span's meaningful name is only known partway
+    /// through its lifetime -- for example, an HTTP server might create a
+    /// span when a request arrives, but not learn which route matched (and
+    /// so what the span should be called) until routing has run. The
+    /// override is stored in the span's [`Extensions`] and is honored by
+    /// [`display_name`], which [`fmt`]'s formatters (and the `json` output)
+    /// call instead of reading the name from `Metadata` directly.
+    ///
+    /// Because callsite [`Metadata`] is `'static` and shared by every
+    /// invocation of the same span, this does not (and cannot) change it:
+    /// [`SpanRef::metadata`] and [`SpanRef::name`] keep returning the
+    /// original, statically-known name, so callsite-level filtering by name
+    /// is unaffected.
+    ///
+    /// [`Metadata`]: tracing_core::Metadata
+    /// [`Extensions`]: crate::registry::Extensions
+    /// [`display_name`]: SpanRef::display_name
+    /// [`fmt`]: crate::fmt
+    pub fn rename(&self, name: impl Into<Cow<'static, str>>) {
+        let mut extensions = self.extensions_mut();
+        match extensions.get_mut::<SpanNameOverride>() {
+            Some(over) => over.name = Some(name.into()),
+            None => extensions.insert(SpanNameOverride {
+                name: Some(name.into()),
+                target: None,
+            }),
+        }
+    }
+
+    /// Overrides the target this span reports to formatters and other
+    /// consumers, without changing its underlying [`Metadata`].
+    ///
+    /// See [`rename`] for why and how this is used; this method is the same
+    /// but for the span's target rather than its name. The override is
+    /// honored by [`display_target`].
+    ///
+    /// [`Metadata`]: tracing_core::Metadata
+    /// [`rename`]: SpanRef::rename
+    /// [`display_target`]: SpanRef::display_target
+    pub fn retarget(&self, target: impl Into<Cow<'static, str>>) {
+        let mut extensions = self.extensions_mut();
+        match extensions.get_mut::<SpanNameOverride>() {
+            Some(over) => over.target = Some(target.into()),
+            None => extensions.insert(SpanNameOverride {
+                name: None,
+                target: Some(target.into()),
+            }),
+        }
+    }
+
+    /// Returns the name this span should be displayed as, honoring any
+    /// override set with [`rename`].
+    ///
+    /// Falls back to [`SpanRef::name`] if no override has been set.
+    ///
+    /// [`rename`]: SpanRef::rename
+    pub fn display_name(&self) -> Cow<'static, str> {
+        match self.extensions().get::<SpanNameOverride>() {
+            Some(SpanNameOverride { name: Some(name), .. }) => name.clone(),
+            _ => Cow::Borrowed(self.name()),
+        }
+    }
+
+    /// Returns the target this span should be displayed as, honoring any
+    /// override set with [`retarget`].
+    ///
+    /// Falls back to the target recorded in the span's [`Metadata`] if no
+    /// override has been set.
+    ///
+    /// [`retarget`]: SpanRef::retarget
+    /// [`Metadata`]: tracing_core::Metadata
+    pub fn display_target(&self) -> Cow<'static, str> {
+        match self.extensions().get::<SpanNameOverride>() {
+            Some(SpanNameOverride { target: Some(target), .. }) => target.clone(),
+            _ => Cow::Borrowed(self.metadata().target()),
+        }
+    }
+}
+
+/// An override for a span's externally-visible name and/or target, installed
+/// with [`SpanRef::rename`]/[`SpanRef::retarget`] and read back with
+/// [`SpanRef::display_name`]/[`SpanRef::display_target`].
+#[derive(Clone, Debug, Default)]
+struct SpanNameOverride {
+    name: Option<Cow<'static, str>>,
+    target: Option<Cow<'static, str>>,
 }
 
 #[cfg(all(test, feature = "registry"))]
@@ -470,4 +641,30 @@ mod tests {
             &["root", "child", "leaf"]
         );
     }
+
+    #[test]
+    fn rename_and_retarget_override_display_but_not_metadata() {
+        let _guard = tracing::collect::set_default(crate::registry());
+
+        let span = tracing::info_span!("original_name");
+        let id = span.id().unwrap();
+
+        tracing::dispatch::get_default(|dispatch| {
+            let registry = dispatch.downcast_ref::<crate::registry::Registry>().unwrap();
+            let span = registry.span(&id).unwrap();
+
+            assert_eq!(span.display_name(), "original_name");
+            assert_eq!(span.display_target(), module_path!());
+
+            span.rename("matched_route");
+            span.retarget("http::routes");
+
+            assert_eq!(span.display_name(), "matched_route");
+            assert_eq!(span.display_target(), "http::routes");
+
+            // The underlying `Metadata` is unaffected.
+            assert_eq!(span.name(), "original_name");
+            assert_eq!(span.metadata().target(), module_path!());
+        });
+    }
 }
@@ -86,10 +86,24 @@ cfg_feature!("registry", {
     /// [ot]: https://github.com/open-telemetry/opentelemetry-specification/blob/main/specification/trace/api.md#spancontext
     /// [fields]: https://docs.rs/tracing-core/latest/tracing-core/field/index.html
     /// [stored span data]: crate::registry::SpanData::extensions_mut
+    ///
+    /// # Memory Usage
+    ///
+    /// The `Registry`'s underlying sharded slab reuses freed span slots
+    /// rather than ever shrinking, and it does not expose an API for
+    /// capping its size or inspecting its shard/page layout. [`Registry`]
+    /// therefore does not offer `max_spans`- or `shrink_policy`-style
+    /// configuration, or a `stats()` method returning allocated pages or
+    /// per-shard occupancy; the underlying slab implementation has no
+    /// hooks for any of that. [`Registry::len`] reports the number of
+    /// currently active spans (tracked separately, via an atomic
+    /// counter), which is the one memory-related signal this type can
+    /// cheaply and honestly provide.
     #[derive(Debug)]
     pub struct Registry {
         spans: Pool<DataInner>,
         current_spans: ThreadLocal<RefCell<SpanStack>>,
+        len: AtomicUsize,
     }
 
     /// Span data stored in a [`Registry`].
@@ -131,10 +145,31 @@ impl Default for Registry {
         Self {
             spans: Pool::new(),
             current_spans: ThreadLocal::new(),
+            len: AtomicUsize::new(0),
         }
     }
 }
 
+impl Registry {
+    /// Returns the number of spans currently active in this registry.
+    ///
+    /// This is tracked independently of the underlying slab's allocated
+    /// capacity: the slab reuses closed spans' slots rather than freeing
+    /// them, so its size only ever grows to the high-water mark of
+    /// concurrently active spans. This method reports how many of those
+    /// allocated slots are currently occupied by a live span, which is
+    /// useful for monitoring, but does not reflect the registry's memory
+    /// footprint.
+    pub fn len(&self) -> usize {
+        self.len.load(Ordering::Relaxed)
+    }
+
+    /// Returns `true` if this registry has no active spans.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
 #[inline]
 fn idx_to_id(idx: usize) -> Id {
     Id::from_u64(idx as u64 + 1)
@@ -235,6 +270,7 @@ impl Collect for Registry {
                 *refs = 1;
             })
             .expect("Unable to allocate another span");
+        self.len.fetch_add(1, Ordering::Relaxed);
         idx_to_id(id)
     }
 
@@ -321,6 +357,7 @@ impl Collect for Registry {
         // from std::Arc); this ensures that all other `try_close` calls on
         // other threads happen-before we actually remove the span.
         fence(Ordering::Acquire);
+        self.len.fetch_sub(1, Ordering::Relaxed);
         true
     }
 }
@@ -846,4 +883,24 @@ mod tests {
             state.assert_closed_in_order(&["child", "parent", "grandparent"]);
         });
     }
+
+    #[test]
+    fn len_tracks_active_spans() {
+        let registry = Registry::default();
+        let dispatch = dispatch::Dispatch::new(registry);
+
+        dispatch::with_default(&dispatch, || {
+            assert_eq!(dispatch.downcast_ref::<Registry>().unwrap().len(), 0);
+
+            let span1 = tracing::info_span!("span1");
+            let span2 = tracing::info_span!("span2");
+            assert_eq!(dispatch.downcast_ref::<Registry>().unwrap().len(), 2);
+
+            drop(span1);
+            assert_eq!(dispatch.downcast_ref::<Registry>().unwrap().len(), 1);
+
+            drop(span2);
+            assert_eq!(dispatch.downcast_ref::<Registry>().unwrap().len(), 0);
+        });
+    }
 }
@@ -0,0 +1,263 @@
+//! Attaching a [`Subscribe`] to a single span subtree, for the span's
+//! lifetime.
+//!
+//! [`SpanCapture`] is a routing [`Subscribe`] that forwards events and span
+//! lifecycle hooks to a separate layer attached to a specific span, for as
+//! long as the [`CaptureGuard`] returned by [`SpanCapture::attach`] is held.
+//! This is meant for capturing everything under one request into a
+//! dedicated buffer or writer -- e.g. when a support ticket references a
+//! single request ID and full-verbosity output is only wanted for that one
+//! subtree -- without reconfiguring (or filtering) the rest of the
+//! subscriber stack.
+//!
+//! This module requires the "span-capture" feature flag.
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+use tracing_core::{collect::Interest, span, Collect, Event, LevelFilter, Metadata};
+
+use crate::registry::{LookupSpan, SpanRef};
+use crate::subscribe::{Context, Subscribe};
+
+type ArcSubscribe<C> = Arc<dyn Subscribe<C> + Send + Sync>;
+
+struct Attached<C>(ArcSubscribe<C>);
+
+// Manual `Clone` impl, since `#[derive(Clone)]` would require `C: Clone`,
+// which isn't actually needed: `Arc<dyn Subscribe<C> + Send + Sync>` is
+// `Clone` regardless of `C`.
+impl<C> Clone for Attached<C> {
+    fn clone(&self) -> Self {
+        Attached(self.0.clone())
+    }
+}
+
+fn nearest_attached<'a, C>(span: &SpanRef<'a, C>) -> Option<ArcSubscribe<C>>
+where
+    C: LookupSpan<'a> + 'static,
+{
+    span.scope()
+        .find_map(|ancestor| ancestor.extensions().get::<Attached<C>>().map(|a| a.0.clone()))
+}
+
+/// A guard that detaches a [`Subscribe`] from the span it was attached to
+/// via [`SpanCapture::attach`], when dropped.
+///
+/// Dropping this guard doesn't affect the span itself -- only the routing
+/// that forwarded events and lifecycle hooks in its subtree to the attached
+/// layer.
+pub struct CaptureGuard<C>
+where
+    C: Collect + for<'a> LookupSpan<'a> + 'static,
+{
+    span: tracing::Span,
+    _subscribe: PhantomData<fn(C)>,
+}
+
+// A derived impl would add a spurious `C: Debug` bound from `_subscribe`'s
+// `PhantomData<fn(C)>`, even though `C` never actually appears in the
+// printed output; `span` is the only field worth showing anyway.
+impl<C> std::fmt::Debug for CaptureGuard<C>
+where
+    C: Collect + for<'a> LookupSpan<'a> + 'static,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CaptureGuard").field("span", &self.span).finish()
+    }
+}
+
+impl<C> Drop for CaptureGuard<C>
+where
+    C: Collect + for<'a> LookupSpan<'a> + 'static,
+{
+    fn drop(&mut self) {
+        self.span.with_collector(|(id, dispatch)| {
+            if let Some(collector) = dispatch.downcast_ref::<C>() {
+                if let Some(span) = collector.span(id) {
+                    span.extensions_mut().remove::<Attached<C>>();
+                }
+            }
+        });
+    }
+}
+
+/// A [`Subscribe`] that forwards events and span lifecycle hooks occurring
+/// in a span's subtree to a separate layer attached to that span, for as
+/// long as the returned [`CaptureGuard`] is held.
+///
+/// A `SpanCapture` itself holds no state; any number of spans may have their
+/// own attached layer at the same time, and nesting is honored -- an event
+/// recorded in a span with no layer of its own is routed to the nearest
+/// ancestor's attached layer, if any.
+///
+/// # Examples
+///
+/// ```
+/// use tracing_subscriber::prelude::*;
+/// use tracing_subscriber::registry::span_capture::SpanCapture;
+/// use tracing_subscriber::testing::CaptureLayer;
+///
+/// let subscriber = tracing_subscriber::registry().with(SpanCapture::<tracing_subscriber::Registry>::new());
+/// tracing::collect::with_default(subscriber, || {
+///     let request = tracing::info_span!("request");
+///
+///     let (capture, handle) = CaptureLayer::new();
+///     let _guard = SpanCapture::<tracing_subscriber::Registry>::attach(&request, capture);
+///
+///     let _entered = request.enter();
+///     tracing::debug!("only visible to this request's capture");
+///     drop(_entered);
+///
+///     assert_eq!(handle.events().len(), 1);
+/// });
+/// ```
+#[derive(Debug, Default)]
+pub struct SpanCapture<C> {
+    _marker: PhantomData<fn(C)>,
+}
+
+impl<C> SpanCapture<C>
+where
+    C: Collect + for<'a> LookupSpan<'a> + 'static,
+{
+    /// Returns a new `SpanCapture`.
+    pub fn new() -> Self {
+        Self {
+            _marker: PhantomData,
+        }
+    }
+
+    /// Attaches `subscriber` to `span`, so that events and lifecycle hooks
+    /// in its subtree are forwarded to it, until the returned
+    /// [`CaptureGuard`] is dropped.
+    ///
+    /// Returns `None` if `span` isn't enabled, or if the current default
+    /// collector isn't the one this `SpanCapture` was composed into.
+    pub fn attach(
+        span: &tracing::Span,
+        subscriber: impl Subscribe<C> + Send + Sync + 'static,
+    ) -> Option<CaptureGuard<C>> {
+        span.with_collector(|(id, dispatch)| {
+            let collector = dispatch.downcast_ref::<C>()?;
+            let span = collector.span(id)?;
+            span.extensions_mut()
+                .insert(Attached(Arc::new(subscriber) as ArcSubscribe<C>));
+            Some(())
+        })??;
+
+        Some(CaptureGuard {
+            span: span.clone(),
+            _subscribe: PhantomData,
+        })
+    }
+}
+
+impl<C> Subscribe<C> for SpanCapture<C>
+where
+    C: Collect + for<'a> LookupSpan<'a> + 'static,
+{
+    fn register_callsite(&self, _metadata: &'static Metadata<'static>) -> Interest {
+        // Whether a callsite is routed to an attached layer depends on
+        // per-span state that doesn't exist until a span is entered, so it
+        // can't be decided once and cached.
+        Interest::always()
+    }
+
+    fn max_level_hint(&self) -> Option<LevelFilter> {
+        // An attached layer's own interest can change at any time, so this
+        // subscriber can't offer a static upper bound.
+        None
+    }
+
+    fn new_span(&self, attrs: &span::Attributes<'_>, id: &span::Id, cx: Context<'_, C>) {
+        let target = cx.span(id).and_then(|span| nearest_attached(&span));
+        if let Some(target) = target {
+            target.new_span(attrs, id, cx);
+        }
+    }
+
+    fn on_event(&self, event: &Event<'_>, cx: Context<'_, C>) {
+        let target = cx.event_span(event).and_then(|span| nearest_attached(&span));
+        if let Some(target) = target {
+            target.on_event(event, cx);
+        }
+    }
+
+    fn on_enter(&self, id: &span::Id, cx: Context<'_, C>) {
+        let target = cx.span(id).and_then(|span| nearest_attached(&span));
+        if let Some(target) = target {
+            target.on_enter(id, cx);
+        }
+    }
+
+    fn on_exit(&self, id: &span::Id, cx: Context<'_, C>) {
+        let target = cx.span(id).and_then(|span| nearest_attached(&span));
+        if let Some(target) = target {
+            target.on_exit(id, cx);
+        }
+    }
+
+    fn on_close(&self, id: span::Id, cx: Context<'_, C>) {
+        let target = cx.span(&id).and_then(|span| nearest_attached(&span));
+        if let Some(target) = target {
+            target.on_close(id, cx);
+        }
+    }
+}
+
+#[cfg(all(test, feature = "testing"))]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+    use crate::testing::CaptureLayer;
+    use crate::Registry;
+
+    #[test]
+    fn events_outside_any_attached_span_are_ignored() {
+        let subscriber = crate::registry().with(SpanCapture::<Registry>::new());
+        let (capture, handle) = CaptureLayer::new();
+
+        tracing::collect::with_default(subscriber, || {
+            let request = tracing::info_span!("request");
+            let _guard = SpanCapture::<Registry>::attach(&request, capture);
+
+            tracing::debug!("before entering the captured span");
+            assert!(handle.events().is_empty());
+        });
+    }
+
+    #[test]
+    fn nested_spans_inherit_the_ancestors_attached_layer() {
+        let subscriber = crate::registry().with(SpanCapture::<Registry>::new());
+        let (capture, handle) = CaptureLayer::new();
+
+        tracing::collect::with_default(subscriber, || {
+            let request = tracing::info_span!("request");
+            let _guard = SpanCapture::<Registry>::attach(&request, capture);
+
+            let _request = request.enter();
+            let _step = tracing::info_span!("step").entered();
+            tracing::debug!("nested inside the captured subtree");
+        });
+
+        assert_eq!(handle.events().len(), 1);
+    }
+
+    #[test]
+    fn detaching_stops_further_routing() {
+        let subscriber = crate::registry().with(SpanCapture::<Registry>::new());
+        let (capture, handle) = CaptureLayer::new();
+
+        tracing::collect::with_default(subscriber, || {
+            let request = tracing::info_span!("request");
+            let guard = SpanCapture::<Registry>::attach(&request, capture);
+
+            let _entered = request.enter();
+            tracing::debug!("captured");
+            drop(guard);
+            tracing::debug!("not captured");
+        });
+
+        assert_eq!(handle.events().len(), 1);
+    }
+}
@@ -0,0 +1,184 @@
+//! Recording per-span busy/idle time in the registry.
+//!
+//! Unlike [`fmt::Subscriber`]'s own span timing (which is only computed when
+//! formatting `enter`/`exit`/`close` lifecycle events, and is private to that
+//! module), [`SpanTiming`] records busy and idle time for every span in the
+//! registry's [`Extensions`], where any downstream [`Subscribe`] (formatters,
+//! exporters, ...) can read it with [`SpanTimings::current`].
+//!
+//! [`fmt::Subscriber`]: crate::fmt::Subscriber
+//! [`Extensions`]: crate::registry::Extensions
+//!
+//! This module requires the "span-timing" feature flag.
+use std::time::{Duration, Instant};
+
+use tracing_core::{span, Collect};
+
+use crate::registry::LookupSpan;
+use crate::subscribe::{Context, Subscribe};
+
+/// The accumulated busy and idle time for a span, as recorded by
+/// [`SpanTiming`].
+///
+/// A reference to this type can be retrieved from a span's [`Extensions`]
+/// with [`SpanTimings::current`].
+///
+/// [`Extensions`]: crate::registry::Extensions
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SpanTimings {
+    /// The total time for which the span was entered.
+    pub busy: Duration,
+    /// The total time between the span being created (or last exited) and
+    /// entered again.
+    pub idle: Duration,
+    last: Option<Instant>,
+}
+
+impl SpanTimings {
+    /// Returns the busy and idle time recorded so far for `id`, if the span
+    /// is known to `ctx` and has been visited by a [`SpanTiming`] subscriber.
+    pub fn current<C>(ctx: &Context<'_, C>, id: &span::Id) -> Option<Self>
+    where
+        C: Collect + for<'a> LookupSpan<'a>,
+    {
+        let span = ctx.span(id)?;
+        let extensions = span.extensions();
+        extensions.get::<Self>().copied()
+    }
+}
+
+/// A [`Subscribe`] that records each span's busy time (the sum of the
+/// durations for which it was entered) and idle time (the time between
+/// creation or exit and the following enter) in that span's [`Extensions`],
+/// and, if constructed with [`SpanTiming::with_close_fields`], attaches
+/// `time.busy`/`time.idle` fields (in nanoseconds) to the span's close event.
+///
+/// [`Extensions`]: crate::registry::Extensions
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SpanTiming {
+    close_fields: bool,
+}
+
+impl SpanTiming {
+    /// Returns a new `SpanTiming` that only records timings in span
+    /// extensions.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Configures this `SpanTiming` to also attach `time.busy`/`time.idle`
+    /// fields, in nanoseconds, to a `tracing`-level event emitted when a
+    /// timed span closes.
+    pub fn with_close_fields(self, close_fields: bool) -> Self {
+        Self { close_fields }
+    }
+}
+
+impl<C> Subscribe<C> for SpanTiming
+where
+    C: Collect + for<'a> LookupSpan<'a>,
+{
+    fn new_span(&self, _attrs: &span::Attributes<'_>, id: &span::Id, ctx: Context<'_, C>) {
+        let span = ctx.span(id).expect("span must exist, this is a bug");
+        span.extensions_mut().insert(SpanTimings {
+            last: Some(Instant::now()),
+            ..Default::default()
+        });
+    }
+
+    fn on_enter(&self, id: &span::Id, ctx: Context<'_, C>) {
+        let span = ctx.span(id).expect("span must exist, this is a bug");
+        let mut extensions = span.extensions_mut();
+        if let Some(timings) = extensions.get_mut::<SpanTimings>() {
+            let now = Instant::now();
+            if let Some(last) = timings.last {
+                timings.idle += now - last;
+            }
+            timings.last = Some(now);
+        }
+    }
+
+    fn on_exit(&self, id: &span::Id, ctx: Context<'_, C>) {
+        let span = ctx.span(id).expect("span must exist, this is a bug");
+        let mut extensions = span.extensions_mut();
+        if let Some(timings) = extensions.get_mut::<SpanTimings>() {
+            let now = Instant::now();
+            if let Some(last) = timings.last {
+                timings.busy += now - last;
+            }
+            timings.last = Some(now);
+        }
+    }
+
+    fn on_close(&self, id: span::Id, ctx: Context<'_, C>) {
+        if !self.close_fields {
+            return;
+        }
+        let span = ctx.span(&id).expect("span must exist, this is a bug");
+        let extensions = span.extensions();
+        if let Some(timings) = extensions.get::<SpanTimings>() {
+            let mut idle = timings.idle;
+            if let Some(last) = timings.last {
+                idle += Instant::now() - last;
+            }
+            let busy = timings.busy;
+            drop(extensions);
+            drop(span);
+            tracing::trace!(
+                target: "tracing_subscriber::registry::timing",
+                span_id = id.into_u64(),
+                time_busy = busy.as_nanos() as u64,
+                time_idle = idle.as_nanos() as u64,
+                "span timing"
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::registry::Registry;
+    use std::sync::{Arc, Mutex};
+    use std::thread;
+    use tracing::collect::with_default;
+    use tracing_core::span::Attributes;
+
+    /// Captures the [`SpanTimings`] recorded for a span just before it
+    /// closes, so the test can assert on them after `with_default` returns.
+    struct CaptureOnClose {
+        captured: Arc<Mutex<Option<SpanTimings>>>,
+    }
+
+    impl<C> Subscribe<C> for CaptureOnClose
+    where
+        C: Collect + for<'a> LookupSpan<'a>,
+    {
+        fn new_span(&self, _: &Attributes<'_>, _id: &span::Id, _ctx: Context<'_, C>) {}
+
+        fn on_close(&self, id: span::Id, ctx: Context<'_, C>) {
+            *self.captured.lock().unwrap() = SpanTimings::current(&ctx, &id);
+        }
+    }
+
+    #[test]
+    fn records_busy_and_idle_time() {
+        let captured = Arc::new(Mutex::new(None));
+        let subscriber = SpanTiming::new()
+            .and_then(CaptureOnClose {
+                captured: captured.clone(),
+            })
+            .with_collector(Registry::default());
+
+        with_default(subscriber, || {
+            let span = tracing::info_span!("timed");
+            {
+                let _enter = span.enter();
+                thread::sleep(Duration::from_millis(5));
+            }
+        });
+
+        let timings = captured.lock().unwrap().expect("timings were recorded");
+        assert!(timings.busy >= Duration::from_millis(5));
+    }
+}
@@ -0,0 +1,372 @@
+//! Recording W3C [Trace Context] identifiers for spans in the registry.
+//!
+//! [`TraceContextLayer`] assigns every span a trace id and span id that
+//! follow the shape of the [W3C Trace Context] `traceparent` header, and
+//! records them in that span's [`Extensions`], where [`TraceContext::current`]
+//! can read them back. A span inherits its trace id (and sampled flag) from
+//! its parent span, unless it's a root span, in which case a new trace id is
+//! generated; a `follows_from` relationship is recorded as a [`Links`] entry
+//! rather than changing the span's own trace id, matching the W3C spec's
+//! treatment of links as separate from parentage.
+//!
+//! This module intentionally stops short of the full propagation machinery
+//! in the `tracing-opentelemetry` crate (no OpenTelemetry SDK dependency, no
+//! `Span` extension trait reaching into the subscriber by downcasting): it
+//! only generates and stores ids, and formats/parses `traceparent` strings.
+//! Accepting a remote parent is done through the well-known
+//! [`REMOTE_PARENT_FIELD`] span field, rather than a separate API, so it
+//! composes with `tracing`'s ordinary span-creation macros.
+//!
+//! This module requires the "trace-context" feature flag.
+//!
+//! [Trace Context]: https://www.w3.org/TR/trace-context/
+//! [W3C Trace Context]: https://www.w3.org/TR/trace-context/
+//! [`Extensions`]: crate::registry::Extensions
+use std::{
+    fmt,
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+use tracing_core::{
+    field::{Field, Visit},
+    span, Collect,
+};
+
+use crate::registry::LookupSpan;
+use crate::subscribe::{Context, Subscribe};
+
+/// The span field name [`TraceContextLayer::new_span`] inspects to seed a
+/// span from a `traceparent` header received on an incoming request, rather
+/// than starting a new trace or inheriting from the local parent span.
+///
+/// # Examples
+///
+/// ```rust
+/// let traceparent = "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01";
+/// let _span = tracing::info_span!("handle_request", trace_context.remote_traceparent = traceparent);
+/// ```
+pub const REMOTE_PARENT_FIELD: &str = "trace_context.remote_traceparent";
+
+/// A 128-bit W3C trace identifier, formatted as 32 lowercase hex digits.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub struct TraceId(u128);
+
+impl fmt::Display for TraceId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:032x}", self.0)
+    }
+}
+
+/// A 64-bit W3C span identifier, formatted as 16 lowercase hex digits.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub struct SpanId(u64);
+
+impl fmt::Display for SpanId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:016x}", self.0)
+    }
+}
+
+/// The W3C trace context recorded for a span: its trace id, its own span id,
+/// and whether the trace is sampled.
+///
+/// A reference to this type can be retrieved from a span's [`Extensions`]
+/// with [`TraceContext::current`].
+///
+/// [`Extensions`]: crate::registry::Extensions
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct TraceContext {
+    /// The id of the trace this span belongs to.
+    pub trace_id: TraceId,
+    /// This span's own id.
+    pub span_id: SpanId,
+    /// Whether this trace is marked as sampled.
+    pub sampled: bool,
+}
+
+impl TraceContext {
+    /// Returns the trace context recorded for `id`, if the span is known to
+    /// `ctx` and has been visited by a [`TraceContextLayer`].
+    pub fn current<C>(ctx: &Context<'_, C>, id: &span::Id) -> Option<Self>
+    where
+        C: Collect + for<'a> LookupSpan<'a>,
+    {
+        let span = ctx.span(id)?;
+        let extensions = span.extensions();
+        extensions.get::<Self>().copied()
+    }
+
+    /// Formats this context as a `traceparent` header value, suitable for
+    /// attaching to an outgoing HTTP request so the callee can continue this
+    /// trace.
+    pub fn traceparent(&self) -> String {
+        format!(
+            "00-{}-{}-{:02x}",
+            self.trace_id, self.span_id, self.sampled as u8
+        )
+    }
+
+    /// Parses a `traceparent` header value received on an incoming request.
+    ///
+    /// The result can be attached to a newly created span through the
+    /// [`REMOTE_PARENT_FIELD`] field so [`TraceContextLayer`] picks it up as
+    /// that span's remote parent.
+    pub fn parse(traceparent: &str) -> Option<Self> {
+        let mut parts = traceparent.trim().split('-');
+        let version = parts.next()?;
+        let trace_id = parts.next()?;
+        let span_id = parts.next()?;
+        let flags = parts.next()?;
+        if parts.next().is_some() {
+            return None;
+        }
+        if version.len() != 2 || trace_id.len() != 32 || span_id.len() != 16 || flags.len() != 2 {
+            return None;
+        }
+
+        let trace_id = u128::from_str_radix(trace_id, 16).ok()?;
+        let span_id = u64::from_str_radix(span_id, 16).ok()?;
+        let flags = u8::from_str_radix(flags, 16).ok()?;
+        if trace_id == 0 || span_id == 0 {
+            return None;
+        }
+
+        Some(Self {
+            trace_id: TraceId(trace_id),
+            span_id: SpanId(span_id),
+            sampled: flags & 0x01 != 0,
+        })
+    }
+}
+
+/// The [`TraceContext`]s of spans this span `follows_from`, recorded in its
+/// [`Extensions`] by [`TraceContextLayer`].
+///
+/// The W3C spec models these as links, distinct from the span's own parent
+/// trace, so they're recorded alongside a span's [`TraceContext`] rather than
+/// overwriting it.
+///
+/// [`Extensions`]: crate::registry::Extensions
+#[derive(Clone, Debug, Default)]
+pub struct Links(pub Vec<TraceContext>);
+
+/// A [`Subscribe`] that assigns each span a [`TraceContext`], inheriting the
+/// trace id from the span's parent (or starting a new trace for a root
+/// span), and records `follows_from` relationships as [`Links`].
+///
+/// See the [module-level docs](self) for how to seed a span from a remote
+/// `traceparent` header.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct TraceContextLayer {
+    _private: (),
+}
+
+impl TraceContextLayer {
+    /// Returns a new `TraceContextLayer`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl<C> Subscribe<C> for TraceContextLayer
+where
+    C: Collect + for<'a> LookupSpan<'a>,
+{
+    fn new_span(&self, attrs: &span::Attributes<'_>, id: &span::Id, ctx: Context<'_, C>) {
+        let mut remote_parent = RemoteParentVisitor(None);
+        attrs.record(&mut remote_parent);
+
+        let trace_context = match remote_parent.0 {
+            Some(remote) => TraceContext {
+                trace_id: remote.trace_id,
+                span_id: next_span_id(),
+                sampled: remote.sampled,
+            },
+            None => {
+                let parent = attrs.parent().cloned().or_else(|| {
+                    if attrs.is_contextual() {
+                        ctx.lookup_current().map(|span| span.id())
+                    } else {
+                        None
+                    }
+                });
+
+                match parent.and_then(|parent_id| TraceContext::current(&ctx, &parent_id)) {
+                    Some(parent) => TraceContext {
+                        trace_id: parent.trace_id,
+                        span_id: next_span_id(),
+                        sampled: parent.sampled,
+                    },
+                    None => TraceContext {
+                        trace_id: next_trace_id(),
+                        span_id: next_span_id(),
+                        sampled: true,
+                    },
+                }
+            }
+        };
+
+        if let Some(span) = ctx.span(id) {
+            span.extensions_mut().insert(trace_context);
+        }
+    }
+
+    fn on_follows_from(&self, id: &span::Id, follows: &span::Id, ctx: Context<'_, C>) {
+        let followed = match TraceContext::current(&ctx, follows) {
+            Some(cx) => cx,
+            None => return,
+        };
+
+        if let Some(span) = ctx.span(id) {
+            let mut extensions = span.extensions_mut();
+            match extensions.get_mut::<Links>() {
+                Some(links) => links.0.push(followed),
+                None => extensions.insert(Links(vec![followed])),
+            }
+        }
+    }
+}
+
+struct RemoteParentVisitor(Option<TraceContext>);
+
+impl Visit for RemoteParentVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == REMOTE_PARENT_FIELD {
+            self.0 = TraceContext::parse(value);
+        }
+    }
+
+    fn record_debug(&mut self, _field: &Field, _value: &dyn fmt::Debug) {}
+}
+
+// A fast, non-cryptographic id generator: splitmix64 seeded from the system
+// clock and perturbed by a monotonically increasing counter, so that ids
+// generated within the same clock tick (or with a coarse clock) still
+// differ. This is sufficient for per-process span/trace identification, but
+// doesn't provide the collision-resistance guarantees of a production
+// OpenTelemetry SDK's id generator.
+static COUNTER: AtomicU64 = AtomicU64::new(0);
+
+fn next_u64() -> u64 {
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_nanos() as u64)
+        .unwrap_or(0);
+    let count = COUNTER.fetch_add(1, Ordering::Relaxed);
+    splitmix64(now ^ count.wrapping_mul(0x9E37_79B9_7F4A_7C15))
+}
+
+fn splitmix64(seed: u64) -> u64 {
+    let x = seed.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    let mut z = x;
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+fn next_trace_id() -> TraceId {
+    TraceId(((next_u64() as u128) << 64) | next_u64() as u128)
+}
+
+fn next_span_id() -> SpanId {
+    // Zero is reserved by the W3C spec to mean "invalid"; regenerate on the
+    // (astronomically unlikely) chance splitmix64 produces it.
+    loop {
+        let id = next_u64();
+        if id != 0 {
+            return SpanId(id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::registry::Registry;
+    use crate::subscribe::CollectExt;
+    use std::sync::{Arc, Mutex};
+    use tracing::collect::with_default;
+    use tracing_core::span::Attributes;
+
+    #[test]
+    fn traceparent_roundtrips() {
+        let header = "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01";
+        let parsed = TraceContext::parse(header).expect("valid traceparent");
+        assert_eq!(parsed.traceparent(), header);
+        assert!(parsed.sampled);
+    }
+
+    #[test]
+    fn rejects_malformed_traceparent() {
+        assert!(TraceContext::parse("not-a-traceparent").is_none());
+        assert!(TraceContext::parse("00-00000000000000000000000000000000-00f067aa0ba902b7-01")
+            .is_none());
+    }
+
+    /// Captures the [`TraceContext`] recorded for each span it sees, keyed by
+    /// span name, so tests can assert on them after `with_default` returns.
+    struct CaptureContexts {
+        captured: Arc<Mutex<Vec<(&'static str, TraceContext)>>>,
+    }
+
+    impl<C> Subscribe<C> for CaptureContexts
+    where
+        C: Collect + for<'a> LookupSpan<'a>,
+    {
+        fn new_span(&self, _: &Attributes<'_>, id: &span::Id, ctx: Context<'_, C>) {
+            let span = ctx.span(id).expect("span must exist, this is a bug");
+            let name = span.name();
+            if let Some(cx) = TraceContext::current(&ctx, id) {
+                self.captured.lock().unwrap().push((name, cx));
+            }
+        }
+    }
+
+    #[test]
+    fn child_spans_inherit_trace_id() {
+        let captured = Arc::new(Mutex::new(Vec::new()));
+        let subscriber = Registry::default().with(TraceContextLayer::new()).with(
+            CaptureContexts {
+                captured: captured.clone(),
+            },
+        );
+
+        with_default(subscriber, || {
+            let parent = tracing::info_span!("parent");
+            let _guard = parent.enter();
+            let _child = tracing::info_span!("child");
+        });
+
+        let captured = captured.lock().unwrap();
+        let parent_cx = captured.iter().find(|(name, _)| *name == "parent").unwrap().1;
+        let child_cx = captured.iter().find(|(name, _)| *name == "child").unwrap().1;
+        assert_eq!(parent_cx.trace_id, child_cx.trace_id);
+        assert_ne!(parent_cx.span_id, child_cx.span_id);
+    }
+
+    #[test]
+    fn remote_parent_seeds_trace_id() {
+        let captured = Arc::new(Mutex::new(Vec::new()));
+        let subscriber = Registry::default().with(TraceContextLayer::new()).with(
+            CaptureContexts {
+                captured: captured.clone(),
+            },
+        );
+        let header = "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01";
+
+        with_default(subscriber, || {
+            let _span = tracing::info_span!(
+                "handle_request",
+                trace_context.remote_traceparent = header
+            );
+        });
+
+        let captured = captured.lock().unwrap();
+        let (_, cx) = captured
+            .iter()
+            .find(|(name, _)| *name == "handle_request")
+            .unwrap();
+        assert_eq!(cx.trace_id.to_string(), "4bf92f3577b34da6a3ce929d0e0e4736");
+        assert!(cx.sampled);
+    }
+}
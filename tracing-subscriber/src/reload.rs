@@ -119,6 +119,52 @@ where
     }
 }
 
+#[cfg(feature = "registry")]
+impl<S, C> crate::filter::Filter<C> for Subscriber<S>
+where
+    S: crate::filter::Filter<C> + 'static,
+{
+    #[inline]
+    fn enabled(&self, metadata: &Metadata<'_>, cx: &subscribe::Context<'_, C>) -> bool {
+        try_lock!(self.inner.read(), else return false).enabled(metadata, cx)
+    }
+
+    #[inline]
+    fn callsite_enabled(&self, metadata: &'static Metadata<'static>) -> Interest {
+        try_lock!(self.inner.read(), else return Interest::sometimes()).callsite_enabled(metadata)
+    }
+
+    #[inline]
+    fn max_level_hint(&self) -> Option<crate::filter::LevelFilter> {
+        try_lock!(self.inner.read(), else return None).max_level_hint()
+    }
+
+    #[inline]
+    fn on_new_span(&self, attrs: &span::Attributes<'_>, id: &span::Id, cx: subscribe::Context<'_, C>) {
+        try_lock!(self.inner.read()).on_new_span(attrs, id, cx)
+    }
+
+    #[inline]
+    fn on_record(&self, id: &span::Id, values: &span::Record<'_>, cx: subscribe::Context<'_, C>) {
+        try_lock!(self.inner.read()).on_record(id, values, cx)
+    }
+
+    #[inline]
+    fn on_enter(&self, id: &span::Id, cx: subscribe::Context<'_, C>) {
+        try_lock!(self.inner.read()).on_enter(id, cx)
+    }
+
+    #[inline]
+    fn on_exit(&self, id: &span::Id, cx: subscribe::Context<'_, C>) {
+        try_lock!(self.inner.read()).on_exit(id, cx)
+    }
+
+    #[inline]
+    fn on_close(&self, id: span::Id, cx: subscribe::Context<'_, C>) {
+        try_lock!(self.inner.read()).on_close(id, cx)
+    }
+}
+
 impl<S> Subscriber<S> {
     /// Wraps the given `Subscribe`, returning a subscriber and a `Handle` that allows
     /// the inner type to be modified at runtime.
@@ -195,6 +241,25 @@ impl<S> Handle<S> {
     }
 }
 
+impl<S: 'static> Handle<S> {
+    /// Extracts a `Handle` from an installed [`Collect`], by downcasting to
+    /// the [`reload::Subscriber`](Subscriber) it was wrapped in when the
+    /// subscriber stack was built.
+    ///
+    /// This is a convenience for the common case where a `reload::Handle`
+    /// wasn't held onto at setup time; it lets code elsewhere in the
+    /// application recover one from whatever collector is currently
+    /// installed, e.g. via [`tracing::dispatch::get_default`].
+    ///
+    /// Returns `None` if `collector` was not composed from a
+    /// `reload::Subscriber<S>` for this particular `S`.
+    pub fn from_collector(collector: &(impl Collect + 'static)) -> Option<Self> {
+        (collector as &dyn Collect)
+            .downcast_ref::<Subscriber<S>>()
+            .map(Subscriber::handle)
+    }
+}
+
 impl<S> Clone for Handle<S> {
     fn clone(&self) -> Self {
         Handle {
@@ -11,6 +11,63 @@ use tracing_core::{
 use crate::registry::{self, LookupSpan, Registry, SpanRef};
 use std::{any::TypeId, marker::PhantomData, ptr::NonNull};
 
+mod dynamic;
+pub use dynamic::{BoxSubscribe, DynamicSubscriber, Handle as DynamicHandle};
+
+mod router;
+pub use router::{Router, RouterBuilder, SINK_FIELD};
+
+#[cfg(feature = "cli")]
+#[cfg_attr(docsrs, doc(cfg(feature = "cli")))]
+pub mod cli;
+
+#[cfg(feature = "service-lifecycle")]
+#[cfg_attr(docsrs, doc(cfg(feature = "service-lifecycle")))]
+mod service_lifecycle;
+#[cfg(feature = "service-lifecycle")]
+#[cfg_attr(docsrs, doc(cfg(feature = "service-lifecycle")))]
+pub use service_lifecycle::ServiceLifecycle;
+
+/// A bitmask declaring which of a [`Subscribe`]'s lifecycle hooks are
+/// actually implemented, returned by [`Subscribe::hook_mask`].
+///
+/// This is a doc-hidden, opt-in optimization hint, not a correctness
+/// mechanism: a subscriber that never overrides `hook_mask` (and thus
+/// reports [`HookMask::ALL`]) behaves identically to one that does.
+#[doc(hidden)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct HookMask(u8);
+
+impl HookMask {
+    /// This subscriber overrides [`Subscribe::new_span`].
+    pub const NEW_SPAN: Self = Self(1 << 0);
+    /// This subscriber overrides [`Subscribe::on_record`].
+    pub const ON_RECORD: Self = Self(1 << 1);
+    /// This subscriber overrides [`Subscribe::on_enter`].
+    pub const ON_ENTER: Self = Self(1 << 2);
+    /// This subscriber overrides [`Subscribe::on_exit`].
+    pub const ON_EXIT: Self = Self(1 << 3);
+    /// This subscriber overrides [`Subscribe::on_close`].
+    pub const ON_CLOSE: Self = Self(1 << 4);
+
+    /// No lifecycle hooks are implemented.
+    pub const NONE: Self = Self(0);
+    /// Every lifecycle hook is implemented; the conservative default.
+    pub const ALL: Self = Self(
+        Self::NEW_SPAN.0 | Self::ON_RECORD.0 | Self::ON_ENTER.0 | Self::ON_EXIT.0 | Self::ON_CLOSE.0,
+    );
+
+    /// Returns a `HookMask` combining `self` and `other`.
+    pub const fn with(self, other: Self) -> Self {
+        Self(self.0 | other.0)
+    }
+
+    /// Returns `true` if this mask includes `hook`.
+    pub const fn contains(self, hook: Self) -> bool {
+        self.0 & hook.0 == hook.0
+    }
+}
+
 /// A composable handler for `tracing` events.
 ///
 /// The [`Collect`] trait in `tracing-core` represents the _complete_ set of
@@ -308,6 +365,29 @@ where
     // seems like a good future-proofing measure as it may grow other methods later...
     fn on_follows_from(&self, _span: &span::Id, _follows: &span::Id, _ctx: Context<'_, C>) {}
 
+    /// Returns `true` if this subscriber wants to be notified about the
+    /// given `event`, via [`on_event`][Self::on_event].
+    ///
+    /// Unlike [`enabled`][Self::enabled], which is evaluated against an
+    /// event's or span's [`Metadata`] before it's known whether a particular
+    /// event is even going to be constructed, `event_enabled` is called with
+    /// the fully-constructed `Event`, after other subscribers further in
+    /// (those composed before this one) have already had a chance to see it.
+    /// This lets a subscriber look at the event's fields -- for example, to
+    /// suppress its own notification for events carrying `internal = true`
+    /// -- which `enabled`'s `Metadata`-only view cannot do.
+    ///
+    /// Returning `false` only opts this subscriber out of `on_event` for
+    /// this particular event; it does not globally disable the event, and
+    /// other subscribers in the stack still have their own `event_enabled`
+    /// and `on_event` called as usual.
+    ///
+    /// The default implementation always returns `true`.
+    fn event_enabled(&self, event: &Event<'_>, ctx: Context<'_, C>) -> bool {
+        let _ = (event, ctx);
+        true
+    }
+
     /// Notifies this subscriber that an event has occurred.
     fn on_event(&self, _event: &Event<'_>, _ctx: Context<'_, C>) {}
 
@@ -324,6 +404,24 @@ where
     /// subscriber returned a different ID.
     fn on_id_change(&self, _old: &span::Id, _new: &span::Id, _ctx: Context<'_, C>) {}
 
+    /// Returns a [`HookMask`] declaring which of this subscriber's lifecycle
+    /// hooks (other than [`on_event`][Self::on_event], which is always
+    /// assumed to matter) are actually overridden with non-default behavior.
+    ///
+    /// [`Filtered`] consults this to skip its enabled-check and `Context`
+    /// cloning for hooks a wrapped subscriber doesn't care about, which
+    /// matters when many `Filtered` subscribers are stacked. The default
+    /// implementation conservatively reports every hook as implemented, so
+    /// subscribers that don't override this method behave exactly as they
+    /// did before it existed; only override it as a targeted optimization
+    /// once you know which hooks your subscriber leaves as no-ops.
+    ///
+    /// [`Filtered`]: crate::filter::Filtered
+    #[doc(hidden)]
+    fn hook_mask(&self) -> HookMask {
+        HookMask::ALL
+    }
+
     /// Composes this subscriber around the given collector, returning a `Layered`
     /// struct implementing `Subscribe`.
     ///
@@ -492,6 +590,26 @@ where
         }
     }
 
+    /// Combines `self` with a [`Filter`], returning a [`Filtered`] subscriber.
+    ///
+    /// The returned [`Filtered`] subscriber will call [`Filter::enabled`] to
+    /// determine whether a given span or event is enabled, and if so, will
+    /// forward it to `self`. This allows per-subscriber filtering, without
+    /// globally disabling the span or event for the rest of the subscriber
+    /// stack.
+    ///
+    /// [`Filter`]: crate::filter::Filter
+    /// [`Filtered`]: crate::filter::Filtered
+    #[cfg(feature = "registry")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "registry")))]
+    fn with_filter<F>(self, filter: F) -> crate::filter::Filtered<Self, F, C>
+    where
+        Self: Sized,
+        F: crate::filter::Filter<C>,
+    {
+        crate::filter::Filtered::new(self, filter)
+    }
+
     #[doc(hidden)]
     unsafe fn downcast_raw(&self, id: TypeId) -> Option<NonNull<()>> {
         if id == TypeId::of::<Self>() {
@@ -541,6 +659,13 @@ pub trait CollectExt: Collect + crate::sealed::Sealed {
 #[derive(Debug)]
 pub struct Context<'a, C> {
     collector: Option<&'a C>,
+    // The `FilterId` of the `Filtered` subscriber currently dispatching
+    // through this `Context`, if any. This lets `span_scope`/`event_scope`
+    // transparently skip ancestor spans that subscriber's filter never
+    // enabled, rather than exposing every span any subscriber in the stack
+    // saw. See `filter::Filtered`.
+    #[cfg(feature = "registry")]
+    filter: Option<crate::filter::layer_filters::FilterId>,
 }
 
 /// A [collector] composed of a collector wrapped by one or more
@@ -625,7 +750,9 @@ where
 
     fn event(&self, event: &Event<'_>) {
         self.inner.event(event);
-        self.subscriber.on_event(event, self.ctx());
+        if self.subscriber.event_enabled(event, self.ctx()) {
+            self.subscriber.on_event(event, self.ctx());
+        }
     }
 
     fn enter(&self, span: &span::Id) {
@@ -729,15 +856,24 @@ where
         }
     }
 
+    #[doc(hidden)]
+    fn hook_mask(&self) -> HookMask {
+        self.inner.hook_mask().with(self.subscriber.hook_mask())
+    }
+
     #[inline]
     fn new_span(&self, attrs: &span::Attributes<'_>, id: &span::Id, ctx: Context<'_, C>) {
-        self.inner.new_span(attrs, id, ctx.clone());
+        if self.inner.hook_mask().contains(HookMask::NEW_SPAN) {
+            self.inner.new_span(attrs, id, ctx.clone());
+        }
         self.subscriber.new_span(attrs, id, ctx);
     }
 
     #[inline]
     fn on_record(&self, span: &span::Id, values: &span::Record<'_>, ctx: Context<'_, C>) {
-        self.inner.on_record(span, values, ctx.clone());
+        if self.inner.hook_mask().contains(HookMask::ON_RECORD) {
+            self.inner.on_record(span, values, ctx.clone());
+        }
         self.subscriber.on_record(span, values, ctx);
     }
 
@@ -747,27 +883,40 @@ where
         self.subscriber.on_follows_from(span, follows, ctx);
     }
 
+    #[inline]
+    fn event_enabled(&self, event: &Event<'_>, ctx: Context<'_, C>) -> bool {
+        self.inner.event_enabled(event, ctx.clone()) && self.subscriber.event_enabled(event, ctx)
+    }
+
     #[inline]
     fn on_event(&self, event: &Event<'_>, ctx: Context<'_, C>) {
         self.inner.on_event(event, ctx.clone());
-        self.subscriber.on_event(event, ctx);
+        if self.subscriber.event_enabled(event, ctx.clone()) {
+            self.subscriber.on_event(event, ctx);
+        }
     }
 
     #[inline]
     fn on_enter(&self, id: &span::Id, ctx: Context<'_, C>) {
-        self.inner.on_enter(id, ctx.clone());
+        if self.inner.hook_mask().contains(HookMask::ON_ENTER) {
+            self.inner.on_enter(id, ctx.clone());
+        }
         self.subscriber.on_enter(id, ctx);
     }
 
     #[inline]
     fn on_exit(&self, id: &span::Id, ctx: Context<'_, C>) {
-        self.inner.on_exit(id, ctx.clone());
+        if self.inner.hook_mask().contains(HookMask::ON_EXIT) {
+            self.inner.on_exit(id, ctx.clone());
+        }
         self.subscriber.on_exit(id, ctx);
     }
 
     #[inline]
     fn on_close(&self, id: span::Id, ctx: Context<'_, C>) {
-        self.inner.on_close(id.clone(), ctx.clone());
+        if self.inner.hook_mask().contains(HookMask::ON_CLOSE) {
+            self.inner.on_close(id.clone(), ctx.clone());
+        }
         self.subscriber.on_close(id, ctx);
     }
 
@@ -816,6 +965,14 @@ where
         }
     }
 
+    #[doc(hidden)]
+    fn hook_mask(&self) -> HookMask {
+        match self {
+            Some(ref inner) => inner.hook_mask(),
+            None => HookMask::NONE,
+        }
+    }
+
     #[inline]
     fn max_level_hint(&self) -> Option<LevelFilter> {
         match self {
@@ -838,6 +995,14 @@ where
         }
     }
 
+    #[inline]
+    fn event_enabled(&self, event: &Event<'_>, ctx: Context<'_, C>) -> bool {
+        match self {
+            Some(ref inner) => inner.event_enabled(event, ctx),
+            None => true,
+        }
+    }
+
     #[inline]
     fn on_event(&self, event: &Event<'_>, ctx: Context<'_, C>) {
         if let Some(ref inner) = self {
@@ -904,6 +1069,8 @@ where
     fn ctx(&self) -> Context<'_, C> {
         Context {
             collector: Some(&self.inner),
+            #[cfg(feature = "registry")]
+            filter: None,
         }
     }
 }
@@ -1167,7 +1334,11 @@ where
     where
         C: for<'lookup> registry::LookupSpan<'lookup>,
     {
-        Some(self.span(id)?.scope())
+        let scope = self.span(id)?.scope();
+        Some(match self.filter {
+            Some(filter_id) => scope.with_filter(filter_id),
+            None => scope,
+        })
     }
 
     /// Returns an iterator over the [stored data] for all the spans in the
@@ -1197,13 +1368,35 @@ where
     where
         C: for<'lookup> registry::LookupSpan<'lookup>,
     {
-        Some(self.event_span(event)?.scope())
+        let scope = self.event_span(event)?.scope();
+        Some(match self.filter {
+            Some(filter_id) => scope.with_filter(filter_id),
+            None => scope,
+        })
     }
 }
 
 impl<'a, C> Context<'a, C> {
     pub(crate) fn none() -> Self {
-        Self { collector: None }
+        Self {
+            collector: None,
+            #[cfg(feature = "registry")]
+            filter: None,
+        }
+    }
+
+    /// Returns a copy of this `Context` that will be attributed to the
+    /// [`Filter`] identified by `filter_id`, so that `span_scope` and
+    /// `event_scope` calls made through it skip spans that filter never
+    /// enabled.
+    ///
+    /// [`Filter`]: crate::filter::Filter
+    #[cfg(feature = "registry")]
+    pub(crate) fn with_filter(self, filter_id: crate::filter::layer_filters::FilterId) -> Self {
+        Self {
+            filter: Some(filter_id),
+            ..self
+        }
     }
 }
 
@@ -1211,7 +1404,11 @@ impl<'a, C> Clone for Context<'a, C> {
     #[inline]
     fn clone(&self) -> Self {
         let collector = self.collector.as_ref().copied();
-        Context { collector }
+        Context {
+            collector,
+            #[cfg(feature = "registry")]
+            filter: self.filter,
+        }
     }
 }
 
@@ -1390,4 +1587,107 @@ pub(crate) mod tests {
             },
         );
     }
+
+    #[test]
+    #[cfg(feature = "registry")]
+    fn event_enabled_opts_a_single_subscriber_out() {
+        use std::sync::{Arc, Mutex};
+        use tracing_core::field::{Field, Visit};
+
+        struct MessageVisitor(Option<String>);
+        impl Visit for MessageVisitor {
+            fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+                if field.name() == "message" {
+                    self.0 = Some(format!("{:?}", value));
+                }
+            }
+        }
+
+        fn message(event: &Event<'_>) -> String {
+            let mut visitor = MessageVisitor(None);
+            event.record(&mut visitor);
+            visitor.0.unwrap_or_default()
+        }
+
+        struct SuppressInternal {
+            seen: Arc<Mutex<Vec<String>>>,
+        }
+
+        impl<S> Subscribe<S> for SuppressInternal
+        where
+            S: Collect + for<'lookup> LookupSpan<'lookup>,
+        {
+            fn event_enabled(&self, event: &Event<'_>, _ctx: Context<'_, S>) -> bool {
+                !event.fields().any(|field| field.name() == "internal")
+            }
+
+            fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+                self.seen.lock().unwrap().push(message(event));
+            }
+        }
+
+        struct SeesEverything {
+            seen: Arc<Mutex<Vec<String>>>,
+        }
+
+        impl<S> Subscribe<S> for SeesEverything
+        where
+            S: Collect + for<'lookup> LookupSpan<'lookup>,
+        {
+            fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+                self.seen.lock().unwrap().push(message(event));
+            }
+        }
+
+        let suppressed = Arc::new(Mutex::new(Vec::new()));
+        let everything = Arc::new(Mutex::new(Vec::new()));
+
+        tracing::collect::with_default(
+            crate::registry()
+                .with(SuppressInternal {
+                    seen: suppressed.clone(),
+                })
+                .with(SeesEverything {
+                    seen: everything.clone(),
+                }),
+            || {
+                tracing::info!(internal = true, "quiet");
+                tracing::info!("loud");
+            },
+        );
+
+        assert_eq!(*suppressed.lock().unwrap(), vec!["loud"]);
+        assert_eq!(*everything.lock().unwrap(), vec!["quiet", "loud"]);
+    }
+
+    #[test]
+    fn hook_mask_defaults_to_all() {
+        assert_eq!(
+            Subscribe::<NopCollector>::hook_mask(&NopSubscriber),
+            HookMask::ALL
+        );
+    }
+
+    #[test]
+    fn layered_hook_mask_is_union_of_children() {
+        struct OnEnterOnly;
+        impl<C: Collect> Subscribe<C> for OnEnterOnly {
+            fn hook_mask(&self) -> HookMask {
+                HookMask::ON_ENTER
+            }
+        }
+
+        struct OnExitOnly;
+        impl<C: Collect> Subscribe<C> for OnExitOnly {
+            fn hook_mask(&self) -> HookMask {
+                HookMask::ON_EXIT
+            }
+        }
+
+        let layered = OnEnterOnly.and_then(OnExitOnly);
+        let mask = Subscribe::<NopCollector>::hook_mask(&layered);
+        assert!(mask.contains(HookMask::ON_ENTER));
+        assert!(mask.contains(HookMask::ON_EXIT));
+        assert!(!mask.contains(HookMask::ON_RECORD));
+    }
 }
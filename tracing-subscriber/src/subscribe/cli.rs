@@ -0,0 +1,169 @@
+//! A [`Subscribe`] that tracks the highest severity level observed, plus
+//! helpers for turning that into a process exit code.
+//!
+//! Command-line tools conventionally exit non-zero when they've logged a
+//! warning or error, even if the operation they were asked to perform
+//! otherwise completed. Doing that with `tracing` alone means hand-rolling
+//! an atomic and a `Subscribe` for every tool; [`MaxSeverity`] is that layer,
+//! written once.
+//!
+//! This module requires the "cli" feature flag.
+use std::sync::{
+    atomic::{AtomicU8, Ordering},
+    Arc,
+};
+
+use tracing_core::{Collect, Event, Level};
+
+use crate::subscribe::{Context, Subscribe};
+
+/// No event has been observed yet.
+const NONE: u8 = 0;
+
+fn to_u8(level: &Level) -> u8 {
+    // Higher severity (`ERROR`) must map to a higher number, the reverse of
+    // `Level`'s own `Ord` impl, so that `fetch_max` below can be used to
+    // track the *most* severe level with a plain atomic.
+    match *level {
+        Level::TRACE => 1,
+        Level::DEBUG => 2,
+        Level::INFO => 3,
+        Level::WARN => 4,
+        Level::ERROR => 5,
+    }
+}
+
+fn from_u8(value: u8) -> Option<Level> {
+    match value {
+        NONE => None,
+        1 => Some(Level::TRACE),
+        2 => Some(Level::DEBUG),
+        3 => Some(Level::INFO),
+        4 => Some(Level::WARN),
+        5 => Some(Level::ERROR),
+        _ => None,
+    }
+}
+
+/// A handle for reading back the highest severity level recorded by a
+/// [`MaxSeverity`] layer.
+///
+/// # Examples
+///
+/// ```no_run
+/// use tracing_subscriber::prelude::*;
+/// use tracing_subscriber::subscribe::cli::{severity_exit_code, MaxSeverity};
+///
+/// let (layer, handle) = MaxSeverity::new();
+/// let subscriber = tracing_subscriber::registry().with(layer);
+///
+/// tracing::collect::with_default(subscriber, || {
+///     tracing::warn!("disk usage above 90%");
+/// });
+///
+/// std::process::exit(severity_exit_code(handle.max_severity()));
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct Handle {
+    max: Arc<AtomicU8>,
+}
+
+impl Handle {
+    /// Returns the highest severity level recorded so far, or `None` if no
+    /// event has been recorded yet.
+    pub fn max_severity(&self) -> Option<Level> {
+        from_u8(self.max.load(Ordering::Relaxed))
+    }
+}
+
+/// A [`Subscribe`] that records the highest severity level of any event it
+/// observes, for later use by [`severity_exit_code`].
+///
+/// Like other [`Subscribe`]s, a `MaxSeverity` only sees the events its own
+/// filtering (if any, e.g. when wrapped in
+/// [`Filtered`][crate::filter::Filtered]) lets through, so a tool can scope
+/// it to only the events that should affect its exit code.
+///
+/// See the [module-level documentation][self] for an example.
+#[derive(Clone, Debug, Default)]
+pub struct MaxSeverity {
+    handle: Handle,
+}
+
+impl MaxSeverity {
+    /// Returns a new `MaxSeverity` and a [`Handle`] for reading back the
+    /// highest severity level it records.
+    pub fn new() -> (Self, Handle) {
+        let handle = Handle::default();
+        (
+            Self {
+                handle: handle.clone(),
+            },
+            handle,
+        )
+    }
+}
+
+impl<C> Subscribe<C> for MaxSeverity
+where
+    C: Collect,
+{
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, C>) {
+        let level = to_u8(event.metadata().level());
+        self.handle.max.fetch_max(level, Ordering::Relaxed);
+    }
+}
+
+/// Maps a severity level to the exit code a CLI tool should use, following
+/// the common convention that a tool should still exit non-zero if it logged
+/// a warning or error, even if it otherwise completed successfully.
+///
+/// | `level`                    | exit code |
+/// |-----------------------------|-----------|
+/// | `None` (no events observed) | `0`       |
+/// | `TRACE`, `DEBUG`, `INFO`     | `0`       |
+/// | `WARN`                       | `1`       |
+/// | `ERROR`                      | `2`       |
+///
+/// # Examples
+///
+/// ```
+/// use tracing::Level;
+/// use tracing_subscriber::subscribe::cli::severity_exit_code;
+///
+/// assert_eq!(severity_exit_code(None), 0);
+/// assert_eq!(severity_exit_code(Some(Level::INFO)), 0);
+/// assert_eq!(severity_exit_code(Some(Level::WARN)), 1);
+/// assert_eq!(severity_exit_code(Some(Level::ERROR)), 2);
+/// ```
+pub fn severity_exit_code(level: Option<Level>) -> i32 {
+    match level {
+        None | Some(Level::TRACE) | Some(Level::DEBUG) | Some(Level::INFO) => 0,
+        Some(Level::WARN) => 1,
+        Some(Level::ERROR) => 2,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+
+    #[test]
+    fn tracks_the_most_severe_level_observed() {
+        let (layer, handle) = MaxSeverity::new();
+        let subscriber = crate::registry().with(layer);
+
+        tracing::collect::with_default(subscriber, || {
+            assert_eq!(handle.max_severity(), None);
+            tracing::info!("starting up");
+            assert_eq!(handle.max_severity(), Some(Level::INFO));
+            tracing::warn!("disk usage above 90%");
+            assert_eq!(handle.max_severity(), Some(Level::WARN));
+            tracing::debug!("a less severe event doesn't lower the max");
+            assert_eq!(handle.max_severity(), Some(Level::WARN));
+            tracing::error!("out of disk space");
+            assert_eq!(handle.max_severity(), Some(Level::ERROR));
+        });
+    }
+}
@@ -0,0 +1,249 @@
+//! A [`Subscribe`] that can have its member subscribers added or removed
+//! after the collector has been installed.
+use std::sync::Arc;
+
+use tracing_core::{
+    collect::{Collect, Interest},
+    span, Event, LevelFilter, Metadata,
+};
+
+use crate::subscribe::{Context, Subscribe};
+use crate::sync::RwLock;
+
+/// A boxed, dynamically-dispatched [`Subscribe`], as stored by
+/// [`DynamicSubscriber`].
+pub type BoxSubscribe<C> = Box<dyn Subscribe<C> + Send + Sync>;
+
+/// A [`Subscribe`] that holds a list of other subscribers, which may be
+/// added or removed at runtime via a [`Handle`], after the subscriber
+/// stack has already been installed as the default collector.
+///
+/// Since each member subscriber's `register_callsite`/`enabled` can change
+/// the set of enabled callsites, adding or removing a member rebuilds the
+/// global callsite interest cache, just like [`reload`](crate::reload)
+/// does.
+pub struct DynamicSubscriber<C> {
+    subscribers: Arc<RwLock<Vec<BoxSubscribe<C>>>>,
+}
+
+/// A handle that can add or remove subscribers from a [`DynamicSubscriber`]
+/// after it has been installed.
+#[derive(Clone)]
+pub struct Handle<C> {
+    subscribers: Arc<RwLock<Vec<BoxSubscribe<C>>>>,
+}
+
+fn fmt_subscribers<C>(
+    subscribers: &RwLock<Vec<BoxSubscribe<C>>>,
+    f: &mut std::fmt::Formatter<'_>,
+    name: &str,
+) -> std::fmt::Result {
+    // The member subscribers are trait objects, so there's nothing
+    // meaningful to print for each one; report how many are registered
+    // instead of the (un-`Debug`) subscribers themselves.
+    match subscribers.try_read() {
+        Ok(subscribers) => f
+            .debug_struct(name)
+            .field("subscribers", &subscribers.len())
+            .finish(),
+        Err(_) => f
+            .debug_struct(name)
+            .field("subscribers", &format_args!("<locked>"))
+            .finish(),
+    }
+}
+
+impl<C> std::fmt::Debug for DynamicSubscriber<C> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        fmt_subscribers(&self.subscribers, f, "DynamicSubscriber")
+    }
+}
+
+impl<C> std::fmt::Debug for Handle<C> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        fmt_subscribers(&self.subscribers, f, "Handle")
+    }
+}
+
+impl<C> DynamicSubscriber<C> {
+    /// Returns a new, initially empty `DynamicSubscriber`, along with a
+    /// [`Handle`] that can be used to add or remove subscribers from it.
+    pub fn new() -> (Self, Handle<C>) {
+        let subscribers = Arc::new(RwLock::new(Vec::new()));
+        (
+            Self {
+                subscribers: subscribers.clone(),
+            },
+            Handle { subscribers },
+        )
+    }
+
+    /// Returns a [`Handle`] that can be used to add or remove subscribers
+    /// from this `DynamicSubscriber`.
+    pub fn handle(&self) -> Handle<C> {
+        Handle {
+            subscribers: self.subscribers.clone(),
+        }
+    }
+}
+
+impl<C: Collect + 'static> Handle<C> {
+    /// Extracts a `Handle` from an installed [`Collect`], by downcasting to
+    /// the [`DynamicSubscriber`] it was wrapped in when the subscriber stack
+    /// was built.
+    ///
+    /// This is what lets an external agent that was loaded after the
+    /// collector was installed -- a debugger, a plugin, anything that
+    /// didn't have the original `Handle` at setup time -- attach a new
+    /// subscriber to the running process. It only needs a reference to the
+    /// installed collector (for example, from
+    /// [`tracing::dispatch::get_default`]), not the `Handle` that was
+    /// returned when the `DynamicSubscriber` was built.
+    ///
+    /// Returns `None` if `collector` was not composed from a
+    /// `DynamicSubscriber<C>` for this particular `C`, i.e. if no extension
+    /// slot was pre-registered in the subscriber stack.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tracing_subscriber::subscribe::{DynamicSubscriber, DynamicHandle};
+    /// use tracing_subscriber::prelude::*;
+    ///
+    /// let (dynamic, _handle) = DynamicSubscriber::new();
+    /// let collector = dynamic.with_collector(tracing_subscriber::registry());
+    ///
+    /// // Later, in code that never saw `_handle` -- for example, an agent
+    /// // attached to the process after it started up -- recover a `Handle`
+    /// // from a reference to the already-installed collector:
+    /// let handle = DynamicHandle::<tracing_subscriber::Registry>::from_collector(&collector)
+    ///     .expect("a DynamicSubscriber should be installed");
+    /// handle.add(tracing_subscriber::fmt::Subscriber::new());
+    /// ```
+    pub fn from_collector(collector: &(impl Collect + 'static)) -> Option<Self> {
+        (collector as &dyn Collect)
+            .downcast_ref::<DynamicSubscriber<C>>()
+            .map(DynamicSubscriber::handle)
+    }
+}
+
+impl<C: Collect> Handle<C> {
+    /// Adds `subscriber`, returning the index it was inserted at, which can
+    /// later be passed to [`remove`](Handle::remove).
+    pub fn add(&self, subscriber: impl Subscribe<C> + Send + Sync + 'static) -> usize {
+        let index = {
+            let mut subscribers = try_lock!(self.subscribers.write(), else return usize::MAX);
+            subscribers.push(Box::new(subscriber));
+            subscribers.len() - 1
+        };
+        tracing_core::callsite::rebuild_interest_cache();
+        index
+    }
+
+    /// Adds an already-boxed `subscriber`, returning the index it was
+    /// inserted at, which can later be passed to [`remove`](Handle::remove).
+    ///
+    /// This is for callers that have already erased several different
+    /// concrete [`Subscribe`] types to a common [`BoxSubscribe`] (for
+    /// example, because which one to use is chosen at runtime) and would
+    /// otherwise have to unbox and reallocate it to call [`add`](Handle::add).
+    pub fn add_boxed(&self, subscriber: BoxSubscribe<C>) -> usize {
+        let index = {
+            let mut subscribers = try_lock!(self.subscribers.write(), else return usize::MAX);
+            subscribers.push(subscriber);
+            subscribers.len() - 1
+        };
+        tracing_core::callsite::rebuild_interest_cache();
+        index
+    }
+
+    /// Removes the subscriber previously returned by [`add`](Handle::add),
+    /// if it is still present.
+    pub fn remove(&self, index: usize) {
+        {
+            let mut subscribers = try_lock!(self.subscribers.write());
+            if index < subscribers.len() {
+                subscribers.remove(index);
+            }
+        }
+        tracing_core::callsite::rebuild_interest_cache();
+    }
+}
+
+impl<C> Subscribe<C> for DynamicSubscriber<C>
+where
+    C: Collect,
+{
+    fn register_callsite(&self, metadata: &'static Metadata<'static>) -> Interest {
+        let subscribers = try_lock!(self.subscribers.read(), else return Interest::sometimes());
+        // If any member subscriber always wants this callsite, or all of
+        // them sometimes do, we can't statically decide; only report
+        // `never` if every member agrees.
+        if subscribers
+            .iter()
+            .all(|s| s.register_callsite(metadata).is_never())
+        {
+            Interest::never()
+        } else {
+            Interest::sometimes()
+        }
+    }
+
+    fn enabled(&self, metadata: &Metadata<'_>, cx: Context<'_, C>) -> bool {
+        let subscribers = try_lock!(self.subscribers.read(), else return false);
+        subscribers.iter().any(|s| s.enabled(metadata, cx.clone()))
+    }
+
+    fn max_level_hint(&self) -> Option<LevelFilter> {
+        let subscribers = try_lock!(self.subscribers.read(), else return None);
+        subscribers
+            .iter()
+            .map(|s| s.max_level_hint())
+            .fold(Some(LevelFilter::OFF), |acc, hint| match (acc, hint) {
+                (Some(a), Some(b)) => Some(std::cmp::max(a, b)),
+                _ => None,
+            })
+    }
+
+    fn new_span(&self, attrs: &span::Attributes<'_>, id: &span::Id, cx: Context<'_, C>) {
+        let subscribers = try_lock!(self.subscribers.read());
+        for s in subscribers.iter() {
+            s.new_span(attrs, id, cx.clone());
+        }
+    }
+
+    fn on_record(&self, id: &span::Id, values: &span::Record<'_>, cx: Context<'_, C>) {
+        let subscribers = try_lock!(self.subscribers.read());
+        for s in subscribers.iter() {
+            s.on_record(id, values, cx.clone());
+        }
+    }
+
+    fn on_event(&self, event: &Event<'_>, cx: Context<'_, C>) {
+        let subscribers = try_lock!(self.subscribers.read());
+        for s in subscribers.iter() {
+            s.on_event(event, cx.clone());
+        }
+    }
+
+    fn on_enter(&self, id: &span::Id, cx: Context<'_, C>) {
+        let subscribers = try_lock!(self.subscribers.read());
+        for s in subscribers.iter() {
+            s.on_enter(id, cx.clone());
+        }
+    }
+
+    fn on_exit(&self, id: &span::Id, cx: Context<'_, C>) {
+        let subscribers = try_lock!(self.subscribers.read());
+        for s in subscribers.iter() {
+            s.on_exit(id, cx.clone());
+        }
+    }
+
+    fn on_close(&self, id: span::Id, cx: Context<'_, C>) {
+        let subscribers = try_lock!(self.subscribers.read());
+        for s in subscribers.iter() {
+            s.on_close(id.clone(), cx.clone());
+        }
+    }
+}
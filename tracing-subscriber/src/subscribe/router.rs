@@ -0,0 +1,271 @@
+//! A [`Subscribe`] that routes each event to a child subscriber chosen by a
+//! field on the event, rather than fanning every event out to every child.
+use std::collections::HashMap;
+
+use tracing_core::{
+    collect::{Collect, Interest},
+    field::{Field, Visit},
+    span, Event, LevelFilter, Metadata,
+};
+
+use crate::subscribe::dynamic::BoxSubscribe;
+use crate::subscribe::{Context, Subscribe};
+
+/// The name of the field callsites set to hint which sink a [`Router`]
+/// should send an event to, e.g. `tracing::info!(sink = "audit", ..)`.
+///
+/// This isn't a reserved word enforced anywhere in `tracing-core`; it's
+/// just the field name [`Router`] looks for.
+pub const SINK_FIELD: &str = "sink";
+
+/// A [`Subscribe`] that sends each event to one of several child
+/// subscribers, chosen by the value of its [`SINK_FIELD`] field, so
+/// callsites can target a specific sink (e.g. `sink = "audit"`) without
+/// encoding that routing into the event's target.
+///
+/// Events with no `sink` field, or a value that doesn't match any
+/// registered route, go to the router's default subscriber, if one was
+/// configured with [`RouterBuilder::default_route`]; otherwise they are
+/// dropped.
+///
+/// Span lifecycle notifications (`new_span`, `on_record`, `on_enter`,
+/// `on_exit`, `on_close`) aren't tied to any one event's `sink` field, so
+/// they're forwarded to every registered subscriber, the same way
+/// [`DynamicSubscriber`] forwards to all of its members.
+///
+/// Construct a `Router` with [`Router::builder`].
+///
+/// [`DynamicSubscriber`]: crate::subscribe::DynamicSubscriber
+pub struct Router<C> {
+    routes: HashMap<String, BoxSubscribe<C>>,
+    default: Option<BoxSubscribe<C>>,
+}
+
+/// Builds a [`Router`] by mapping [`SINK_FIELD`] values to child
+/// subscribers.
+pub struct RouterBuilder<C> {
+    routes: HashMap<String, BoxSubscribe<C>>,
+    default: Option<BoxSubscribe<C>>,
+}
+
+// The child subscribers are trait objects, so there's nothing meaningful to
+// print for each one; report the registered sink names and whether a
+// default route is set instead of the (un-`Debug`) subscribers themselves.
+
+impl<C> std::fmt::Debug for Router<C> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Router")
+            .field("routes", &self.routes.keys().collect::<Vec<_>>())
+            .field("has_default", &self.default.is_some())
+            .finish()
+    }
+}
+
+impl<C> std::fmt::Debug for RouterBuilder<C> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RouterBuilder")
+            .field("routes", &self.routes.keys().collect::<Vec<_>>())
+            .field("has_default", &self.default.is_some())
+            .finish()
+    }
+}
+
+impl<C> Router<C> {
+    /// Returns a new, empty [`RouterBuilder`].
+    pub fn builder() -> RouterBuilder<C> {
+        RouterBuilder {
+            routes: HashMap::new(),
+            default: None,
+        }
+    }
+
+    fn sink_field(event: &Event<'_>) -> Option<String> {
+        struct SinkVisitor(Option<String>);
+        impl Visit for SinkVisitor {
+            fn record_str(&mut self, field: &Field, value: &str) {
+                if field.name() == SINK_FIELD {
+                    self.0 = Some(value.to_owned());
+                }
+            }
+
+            fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+                if field.name() == SINK_FIELD && self.0.is_none() {
+                    self.0 = Some(format!("{:?}", value));
+                }
+            }
+        }
+
+        let mut visitor = SinkVisitor(None);
+        event.record(&mut visitor);
+        visitor.0
+    }
+
+    fn route_for(&self, event: &Event<'_>) -> Option<&BoxSubscribe<C>> {
+        match Self::sink_field(event) {
+            Some(sink) => self.routes.get(&sink).or(self.default.as_ref()),
+            None => self.default.as_ref(),
+        }
+    }
+}
+
+impl<C: Collect> RouterBuilder<C> {
+    /// Routes events whose `sink` field is `hint` to `subscriber`.
+    ///
+    /// If `route` is called more than once for the same `hint`, the last
+    /// call wins.
+    pub fn route(mut self, hint: impl Into<String>, subscriber: impl Subscribe<C> + Send + Sync + 'static) -> Self {
+        self.routes.insert(hint.into(), Box::new(subscriber));
+        self
+    }
+
+    /// Sets the subscriber that receives events with no `sink` field, or
+    /// whose `sink` field doesn't match any route added with
+    /// [`route`](Self::route).
+    ///
+    /// If this is never called, unmatched events are dropped.
+    pub fn default_route(mut self, subscriber: impl Subscribe<C> + Send + Sync + 'static) -> Self {
+        self.default = Some(Box::new(subscriber));
+        self
+    }
+
+    /// Finishes building the [`Router`].
+    pub fn build(self) -> Router<C> {
+        Router {
+            routes: self.routes,
+            default: self.default,
+        }
+    }
+}
+
+impl<C> Subscribe<C> for Router<C>
+where
+    C: Collect,
+{
+    fn register_callsite(&self, metadata: &'static Metadata<'static>) -> Interest {
+        // Which route an event takes depends on a field value that's only
+        // known once the event fires, so we can't decide statically; ask
+        // for the callsite if any route (or the default) would want it.
+        let wants_it = self.default.is_some()
+            || self
+                .routes
+                .values()
+                .any(|s| !s.register_callsite(metadata).is_never());
+        if wants_it {
+            Interest::sometimes()
+        } else {
+            Interest::never()
+        }
+    }
+
+    fn enabled(&self, metadata: &Metadata<'_>, cx: Context<'_, C>) -> bool {
+        self.routes
+            .values()
+            .any(|s| s.enabled(metadata, cx.clone()))
+            || self.default.as_ref().is_some_and(|s| s.enabled(metadata, cx))
+    }
+
+    fn max_level_hint(&self) -> Option<LevelFilter> {
+        self.routes
+            .values()
+            .chain(self.default.iter())
+            .map(|s| s.max_level_hint())
+            .fold(Some(LevelFilter::OFF), |acc, hint| match (acc, hint) {
+                (Some(a), Some(b)) => Some(std::cmp::max(a, b)),
+                _ => None,
+            })
+    }
+
+    fn new_span(&self, attrs: &span::Attributes<'_>, id: &span::Id, cx: Context<'_, C>) {
+        for s in self.routes.values().chain(self.default.iter()) {
+            s.new_span(attrs, id, cx.clone());
+        }
+    }
+
+    fn on_record(&self, id: &span::Id, values: &span::Record<'_>, cx: Context<'_, C>) {
+        for s in self.routes.values().chain(self.default.iter()) {
+            s.on_record(id, values, cx.clone());
+        }
+    }
+
+    fn on_event(&self, event: &Event<'_>, cx: Context<'_, C>) {
+        if let Some(s) = self.route_for(event) {
+            s.on_event(event, cx);
+        }
+    }
+
+    fn on_enter(&self, id: &span::Id, cx: Context<'_, C>) {
+        for s in self.routes.values().chain(self.default.iter()) {
+            s.on_enter(id, cx.clone());
+        }
+    }
+
+    fn on_exit(&self, id: &span::Id, cx: Context<'_, C>) {
+        for s in self.routes.values().chain(self.default.iter()) {
+            s.on_exit(id, cx.clone());
+        }
+    }
+
+    fn on_close(&self, id: span::Id, cx: Context<'_, C>) {
+        for s in self.routes.values().chain(self.default.iter()) {
+            s.on_close(id.clone(), cx.clone());
+        }
+    }
+}
+
+#[cfg(all(test, feature = "registry"))]
+mod tests {
+    use super::*;
+    use crate::{prelude::*, registry};
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Clone, Default)]
+    struct RecordingSubscribe(Arc<Mutex<Vec<String>>>);
+
+    impl<C: Collect> Subscribe<C> for RecordingSubscribe {
+        fn on_event(&self, event: &Event<'_>, _cx: Context<'_, C>) {
+            let mut messages = Vec::new();
+            struct MessageVisitor<'a>(&'a mut Vec<String>);
+            impl<'a> Visit for MessageVisitor<'a> {
+                fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+                    if field.name() == "message" {
+                        self.0.push(format!("{:?}", value));
+                    }
+                }
+            }
+            event.record(&mut MessageVisitor(&mut messages));
+            self.0.lock().unwrap().extend(messages);
+        }
+    }
+
+    #[test]
+    fn routes_by_sink_field() {
+        let audit = RecordingSubscribe::default();
+        let debug = RecordingSubscribe::default();
+        let fallback = RecordingSubscribe::default();
+
+        let router = Router::builder()
+            .route("audit", audit.clone())
+            .route("debug", debug.clone())
+            .default_route(fallback.clone());
+
+        let _guard = registry::Registry::default()
+            .with(router.build())
+            .set_default();
+
+        tracing::info!(sink = "audit", "user logged in");
+        tracing::info!(sink = "debug", "connection reset");
+        tracing::info!("no sink hint");
+
+        // The "startup-banner" feature, if enabled, also emits an
+        // unrelated event with no `sink` field when the subscriber is
+        // installed, so it lands in the fallback route too.
+        assert_eq!(&*audit.0.lock().unwrap(), &["user logged in"]);
+        assert_eq!(&*debug.0.lock().unwrap(), &["connection reset"]);
+        assert!(fallback
+            .0
+            .lock()
+            .unwrap()
+            .iter()
+            .any(|msg| msg == "no sink hint"));
+    }
+}
@@ -0,0 +1,304 @@
+//! A [`Subscribe`] that turns selected lifecycle events into service
+//! supervisor notifications: [`sd_notify`] messages on systemd, and the
+//! equivalent status APIs on Windows service hosts.
+//!
+//! [`sd_notify`]: https://www.freedesktop.org/software/systemd/man/sd_notify.html
+use tracing_core::{span, Collect, Event, LevelFilter, Metadata};
+
+use crate::subscribe::{Context, Subscribe};
+
+/// Targets [`ServiceLifecycle`] treats as lifecycle notifications, rather
+/// than ordinary events.
+const READY_TARGET: &str = "lifecycle::ready";
+const STATUS_TARGET: &str = "lifecycle::status";
+const WATCHDOG_TARGET: &str = "lifecycle::watchdog";
+
+/// A [`Subscribe`] that watches for events at the `lifecycle::*` targets and
+/// forwards them to whatever service supervisor started this process,
+/// instead of (or in addition to) whatever else is logging them.
+///
+/// Three targets are recognized:
+///
+/// - `lifecycle::ready` -- the process has finished starting up. Sends
+///   systemd's `READY=1`, or (see [platform support](#platform-support))
+///   transitions a Windows service to `SERVICE_RUNNING`.
+/// - `lifecycle::status` -- the event's `message` field becomes the
+///   supervisor's free-text status string, i.e. systemd's `STATUS=...`.
+/// - `lifecycle::watchdog` -- pings the supervisor's watchdog, i.e.
+///   systemd's `WATCHDOG=1`.
+///
+/// Events at any other target are ignored, and pass through to the rest of
+/// the subscriber stack unaffected.
+///
+/// Since neither notification channel is span-aware, this subscriber has no
+/// effect on span lifecycle hooks.
+///
+/// ## Platform support
+///
+/// On Linux, when this process was started by systemd with `Type=notify`
+/// (detected via the `NOTIFY_SOCKET` environment variable systemd sets),
+/// all three targets are honored via the `sd_notify` protocol. Elsewhere on
+/// Unix, or when `NOTIFY_SOCKET` isn't set, all three are no-ops.
+///
+/// On Windows, `lifecycle::ready` transitions the service passed to
+/// [`ServiceLifecycle::for_windows_service`] to `SERVICE_RUNNING` via
+/// `SetServiceStatus`. `lifecycle::status` and `lifecycle::watchdog` are
+/// no-ops there: the classic service control manager API has no equivalent
+/// of a free-text status string or a supervisor watchdog ping, only the
+/// fixed set of `SERVICE_STATE` codes.
+///
+/// This subscriber intentionally does not depend on a Windows-service
+/// crate to obtain that handle: plug in whichever one your service host
+/// already uses, and pass this subscriber the raw `SERVICE_STATUS_HANDLE`
+/// (as a `usize`) it already produced from `RegisterServiceCtrlHandlerExW`.
+#[derive(Debug)]
+pub struct ServiceLifecycle {
+    #[cfg(unix)]
+    notifier: Option<sd_notify::Notifier>,
+    #[cfg(windows)]
+    service: Option<windows_service::ServiceHandle>,
+}
+
+impl ServiceLifecycle {
+    /// Returns a `ServiceLifecycle` that notifies systemd via the
+    /// `NOTIFY_SOCKET` environment variable, if this process was started
+    /// with `Type=notify`; otherwise, all lifecycle events are no-ops.
+    ///
+    /// On non-Unix platforms, this is equivalent to [`ServiceLifecycle::noop`].
+    #[allow(clippy::let_and_return)] // clarity: name the branch outcome
+    pub fn from_env() -> Self {
+        #[cfg(unix)]
+        let notifier = sd_notify::Notifier::from_env();
+        Self {
+            #[cfg(unix)]
+            notifier,
+            #[cfg(windows)]
+            service: None,
+        }
+    }
+
+    /// Returns a `ServiceLifecycle` that reports `lifecycle::ready` to the
+    /// Windows service control manager via `handle`, obtained from your
+    /// service host's `RegisterServiceCtrlHandlerExW` call.
+    ///
+    /// `lifecycle::status` and `lifecycle::watchdog` remain no-ops; see
+    /// [platform support](Self#platform-support).
+    #[cfg(windows)]
+    pub fn for_windows_service(handle: windows_service::ServiceHandle) -> Self {
+        Self {
+            service: Some(handle),
+        }
+    }
+
+    /// Returns a `ServiceLifecycle` for which every lifecycle event is a
+    /// no-op, e.g. for tests, or platforms with no service supervisor.
+    pub fn noop() -> Self {
+        Self {
+            #[cfg(unix)]
+            notifier: None,
+            #[cfg(windows)]
+            service: None,
+        }
+    }
+
+    fn message_field(event: &Event<'_>) -> Option<String> {
+        struct MessageVisitor(Option<String>);
+        impl tracing_core::field::Visit for MessageVisitor {
+            fn record_debug(&mut self, field: &tracing_core::Field, value: &dyn std::fmt::Debug) {
+                if field.name() == "message" {
+                    self.0 = Some(format!("{:?}", value));
+                }
+            }
+        }
+        let mut visitor = MessageVisitor(None);
+        event.record(&mut visitor);
+        visitor.0
+    }
+}
+
+impl<C> Subscribe<C> for ServiceLifecycle
+where
+    C: Collect,
+{
+    fn enabled(&self, metadata: &Metadata<'_>, _cx: Context<'_, C>) -> bool {
+        matches!(
+            metadata.target(),
+            READY_TARGET | STATUS_TARGET | WATCHDOG_TARGET
+        )
+    }
+
+    fn max_level_hint(&self) -> Option<LevelFilter> {
+        // We only ever look at three specific targets, but we can't express
+        // a target-scoped hint here, so don't narrow the level either.
+        None
+    }
+
+    fn on_event(&self, event: &Event<'_>, _cx: Context<'_, C>) {
+        match event.metadata().target() {
+            READY_TARGET => {
+                #[cfg(unix)]
+                if let Some(notifier) = &self.notifier {
+                    notifier.send("READY=1");
+                }
+                #[cfg(windows)]
+                if let Some(service) = &self.service {
+                    service.set_running();
+                }
+            }
+            STATUS_TARGET => {
+                #[cfg(unix)]
+                if let Some(notifier) = &self.notifier {
+                    let status = Self::message_field(event).unwrap_or_default();
+                    notifier.send(&format!("STATUS={}", status));
+                }
+                // No Windows equivalent; see "Platform support" above.
+            }
+            WATCHDOG_TARGET => {
+                #[cfg(unix)]
+                if let Some(notifier) = &self.notifier {
+                    notifier.send("WATCHDOG=1");
+                }
+                // No Windows equivalent; see "Platform support" above.
+            }
+            _ => {}
+        }
+    }
+
+    fn new_span(&self, _attrs: &span::Attributes<'_>, _id: &span::Id, _cx: Context<'_, C>) {}
+    fn on_record(&self, _id: &span::Id, _values: &span::Record<'_>, _cx: Context<'_, C>) {}
+    fn on_enter(&self, _id: &span::Id, _cx: Context<'_, C>) {}
+    fn on_exit(&self, _id: &span::Id, _cx: Context<'_, C>) {}
+    fn on_close(&self, _id: span::Id, _cx: Context<'_, C>) {}
+}
+
+/// The `sd_notify` protocol: a single datagram per notification, sent to
+/// the `AF_UNIX` socket named by the `NOTIFY_SOCKET` environment variable.
+#[cfg(unix)]
+mod sd_notify {
+    use std::env;
+    use std::ffi::OsString;
+    use std::os::unix::ffi::OsStringExt;
+    use std::os::unix::net::UnixDatagram;
+
+    #[derive(Debug)]
+    pub(super) struct Notifier {
+        socket: UnixDatagram,
+    }
+
+    impl Notifier {
+        pub(super) fn from_env() -> Option<Self> {
+            let path = env::var_os("NOTIFY_SOCKET")?;
+            let mut bytes = path.into_vec();
+            if bytes.first() == Some(&b'@') {
+                // Linux abstract sockets are addressed with a leading NUL
+                // byte; systemd spells that as a leading '@' in the
+                // environment variable.
+                bytes[0] = 0;
+            }
+            let addr = OsString::from_vec(bytes);
+            let socket = UnixDatagram::unbound().ok()?;
+            socket.connect(&addr).ok()?;
+            Some(Self { socket })
+        }
+
+        pub(super) fn send(&self, message: &str) {
+            // Notifications are best-effort: there's no reasonable way to
+            // surface a send failure from inside `on_event`, and systemd
+            // itself treats them as fire-and-forget.
+            let _ = self.socket.send(message.as_bytes());
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use std::os::unix::ffi::OsStrExt;
+
+        #[test]
+        fn abstract_socket_address_translates_leading_marker() {
+            let path = std::ffi::OsStr::new("@my.notify.socket");
+            let mut bytes = path.as_bytes().to_vec();
+            assert_eq!(bytes[0], b'@');
+            bytes[0] = 0;
+            assert_eq!(bytes[0], 0);
+            assert_eq!(&bytes[1..], b"my.notify.socket");
+        }
+
+        #[test]
+        fn sends_ready_and_status_datagrams() {
+            let dir = std::env::temp_dir().join(format!(
+                "tracing-subscriber-sd-notify-test-{:?}",
+                std::thread::current().id()
+            ));
+            let _ = std::fs::remove_file(&dir);
+            let listener = UnixDatagram::bind(&dir).expect("failed to bind test socket");
+
+            std::env::set_var("NOTIFY_SOCKET", &dir);
+            let notifier = Notifier::from_env().expect("NOTIFY_SOCKET should be recognized");
+            std::env::remove_var("NOTIFY_SOCKET");
+
+            notifier.send("READY=1");
+            let mut buf = [0u8; 64];
+            let n = listener.recv(&mut buf).expect("expected a datagram");
+            assert_eq!(&buf[..n], b"READY=1");
+
+            notifier.send("STATUS=starting up");
+            let n = listener.recv(&mut buf).expect("expected a datagram");
+            assert_eq!(&buf[..n], b"STATUS=starting up");
+
+            let _ = std::fs::remove_file(&dir);
+        }
+    }
+}
+
+/// Raw FFI bindings for the handful of `advapi32.dll` service-status
+/// functions this subscriber needs, so that using it doesn't require
+/// pulling in a Windows-service crate.
+#[cfg(windows)]
+mod windows_service {
+    const SERVICE_RUNNING: u32 = 0x00000004;
+    const SERVICE_ACCEPT_STOP: u32 = 0x00000001;
+    const NO_ERROR: u32 = 0;
+
+    #[repr(C)]
+    struct ServiceStatus {
+        service_type: u32,
+        current_state: u32,
+        controls_accepted: u32,
+        win32_exit_code: u32,
+        service_specific_exit_code: u32,
+        check_point: u32,
+        wait_hint: u32,
+    }
+
+    #[link(name = "advapi32")]
+    extern "system" {
+        fn SetServiceStatus(handle: isize, status: *const ServiceStatus) -> i32;
+    }
+
+    /// A raw `SERVICE_STATUS_HANDLE`, obtained from your service host's
+    /// `RegisterServiceCtrlHandlerExW` call.
+    ///
+    /// See [`ServiceLifecycle::for_windows_service`](super::ServiceLifecycle::for_windows_service).
+    #[derive(Debug)]
+    pub struct ServiceHandle(pub usize);
+
+    impl ServiceHandle {
+        pub(super) fn set_running(&self) {
+            let status = ServiceStatus {
+                service_type: 0x00000010, // SERVICE_WIN32_OWN_PROCESS
+                current_state: SERVICE_RUNNING,
+                controls_accepted: SERVICE_ACCEPT_STOP,
+                win32_exit_code: NO_ERROR,
+                service_specific_exit_code: 0,
+                check_point: 0,
+                wait_hint: 0,
+            };
+            // Best-effort, like the systemd side: there's no reasonable way
+            // to surface a failure from inside `on_event`.
+            unsafe {
+                SetServiceStatus(self.0 as isize, &status);
+            }
+        }
+    }
+}
@@ -7,12 +7,22 @@
 //! API than `std::sync::RwLock` (it does not support poisoning on panics), we
 //! wrap it with a type that provides the same method signatures. This allows us
 //! to transparently swap `parking_lot` in without changing code at the callsite.
+//!
+//! When built with `--cfg loom` for the `loom`-based concurrency tests (see
+//! `filter::layer_filters`'s `loom` test module for an example), `RwLock` is
+//! swapped for `loom::sync::RwLock` instead, so that loom can explore the
+//! interleavings of code that goes through this module.
 #[allow(unused_imports)] // may be used later;
 pub(crate) use std::sync::{LockResult, PoisonError, TryLockResult};
 
+#[cfg(all(test, loom))]
+pub(crate) use loom::sync::{RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+#[cfg(not(all(test, loom)))]
 #[cfg(not(feature = "parking_lot"))]
 pub(crate) use std::sync::{RwLock, RwLockReadGuard, RwLockWriteGuard};
 
+#[cfg(not(all(test, loom)))]
 #[cfg(feature = "parking_lot")]
 pub(crate) use self::parking_lot_impl::*;
 
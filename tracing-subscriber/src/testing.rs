@@ -0,0 +1,271 @@
+//! A [`Subscribe`] for capturing spans and events emitted during a test.
+//!
+//! Writing an integration test for something that emits `tracing` data
+//! usually means hand-rolling a small `Subscribe`/`Collect` just to record
+//! what happened, so the test can assert on it. [`CaptureLayer`] is that
+//! subscriber, written once: it records every event and the fields and
+//! parent of every span, and hands back a [`Handle`] the test can inspect.
+//!
+//! This module requires the "testing" feature flag.
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use tracing_core::{
+    field::{Field, Visit},
+    span, Collect, Event, Level,
+};
+
+use crate::registry::LookupSpan;
+use crate::subscribe::{Context, Subscribe};
+
+/// An event captured by a [`CaptureLayer`].
+#[derive(Clone, Debug)]
+pub struct CapturedEvent {
+    /// The event's target.
+    pub target: &'static str,
+    /// The event's verbosity level.
+    pub level: Level,
+    /// The event's fields, keyed by field name.
+    pub fields: HashMap<&'static str, String>,
+    /// The `Id` of the span the event was recorded in, if any.
+    pub parent: Option<span::Id>,
+}
+
+/// A span captured by a [`CaptureLayer`].
+#[derive(Clone, Debug)]
+pub struct CapturedSpan {
+    /// The span's `Id`.
+    pub id: span::Id,
+    /// The span's name.
+    pub name: &'static str,
+    /// The span's target.
+    pub target: &'static str,
+    /// The span's verbosity level.
+    pub level: Level,
+    /// The fields recorded on the span over its lifetime (from its creation
+    /// and any subsequent `record` calls), keyed by field name.
+    pub fields: HashMap<&'static str, String>,
+    /// The `Id` of this span's parent, if any.
+    pub parent: Option<span::Id>,
+    /// Whether the span has closed.
+    pub is_closed: bool,
+}
+
+#[derive(Debug, Default)]
+struct Inner {
+    events: Vec<CapturedEvent>,
+    spans: HashMap<span::Id, CapturedSpan>,
+}
+
+/// A handle for inspecting the spans and events recorded by a
+/// [`CaptureLayer`].
+///
+/// # Examples
+///
+/// ```
+/// use tracing_subscriber::prelude::*;
+/// use tracing_subscriber::testing::CaptureLayer;
+/// use tracing::Level;
+///
+/// let (layer, handle) = CaptureLayer::new();
+/// let subscriber = tracing_subscriber::registry().with(layer);
+///
+/// tracing::collect::with_default(subscriber, || {
+///     let _span = tracing::info_span!("request", path = "/users").entered();
+///     tracing::warn!(status = 404, "not found");
+/// });
+///
+/// handle.assert_event(Level::WARN, module_path!(), |fields| {
+///     fields.get("status").map(String::as_str) == Some("404")
+/// });
+/// assert_eq!(handle.spans()[0].name, "request");
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct Handle {
+    inner: Arc<Mutex<Inner>>,
+}
+
+impl Handle {
+    /// Returns a snapshot of every event recorded so far, in the order they
+    /// were recorded.
+    pub fn events(&self) -> Vec<CapturedEvent> {
+        try_lock!(self.inner.lock(), else return Vec::new())
+            .events
+            .clone()
+    }
+
+    /// Returns a snapshot of every span recorded so far. Closed spans retain
+    /// their final field values and are marked [`CapturedSpan::is_closed`].
+    pub fn spans(&self) -> Vec<CapturedSpan> {
+        try_lock!(self.inner.lock(), else return Vec::new())
+            .spans
+            .values()
+            .cloned()
+            .collect()
+    }
+
+    /// Asserts that at least one captured event matches `level`, `target`,
+    /// and `field_matcher`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no captured event matches, printing every captured event to
+    /// aid debugging.
+    pub fn assert_event<F>(&self, level: Level, target: &str, field_matcher: F)
+    where
+        F: Fn(&HashMap<&'static str, String>) -> bool,
+    {
+        let events = self.events();
+        let found = events
+            .iter()
+            .any(|event| event.level == level && event.target == target && field_matcher(&event.fields));
+        assert!(
+            found,
+            "no captured event matched level={:?} target={:?}; captured events: {:#?}",
+            level, target, events
+        );
+    }
+}
+
+/// A [`Subscribe`] that records every event and span, for inspection via a
+/// [`Handle`] in tests.
+///
+/// Like other [`Subscribe`]s, a `CaptureLayer` only sees the spans and
+/// events its own filtering (if any, e.g. when wrapped in
+/// [`Filtered`][crate::filter::Filtered]) lets through, so it composes with
+/// the rest of the per-layer filtering system rather than needing special
+/// handling.
+///
+/// See the [module-level documentation][self] for an example.
+#[derive(Clone, Debug, Default)]
+pub struct CaptureLayer {
+    handle: Handle,
+}
+
+impl CaptureLayer {
+    /// Returns a new `CaptureLayer` and a [`Handle`] for inspecting what it
+    /// captures.
+    pub fn new() -> (Self, Handle) {
+        let handle = Handle::default();
+        (
+            Self {
+                handle: handle.clone(),
+            },
+            handle,
+        )
+    }
+}
+
+struct RecordedFields(HashMap<&'static str, String>);
+
+impl Visit for RecordedFields {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.0.insert(field.name(), value.to_string());
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        self.0.insert(field.name(), format!("{:?}", value));
+    }
+}
+
+impl<C> Subscribe<C> for CaptureLayer
+where
+    C: Collect + for<'a> LookupSpan<'a>,
+{
+    fn new_span(&self, attrs: &span::Attributes<'_>, id: &span::Id, ctx: Context<'_, C>) {
+        let span = match ctx.span(id) {
+            Some(span) => span,
+            None => return,
+        };
+        let mut fields = RecordedFields(HashMap::new());
+        attrs.record(&mut fields);
+        let mut inner = try_lock!(self.handle.inner.lock(), else return);
+        inner.spans.insert(
+            id.clone(),
+            CapturedSpan {
+                id: id.clone(),
+                name: span.name(),
+                target: span.metadata().target(),
+                level: *span.metadata().level(),
+                fields: fields.0,
+                parent: span.parent_id().cloned(),
+                is_closed: false,
+            },
+        );
+    }
+
+    fn on_record(&self, id: &span::Id, values: &span::Record<'_>, _ctx: Context<'_, C>) {
+        let mut inner = try_lock!(self.handle.inner.lock(), else return);
+        if let Some(span) = inner.spans.get_mut(id) {
+            let mut fields = RecordedFields(std::mem::take(&mut span.fields));
+            values.record(&mut fields);
+            span.fields = fields.0;
+        }
+    }
+
+    fn on_close(&self, id: span::Id, _ctx: Context<'_, C>) {
+        let mut inner = try_lock!(self.handle.inner.lock(), else return);
+        if let Some(span) = inner.spans.get_mut(&id) {
+            span.is_closed = true;
+        }
+    }
+
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, C>) {
+        let mut fields = RecordedFields(HashMap::new());
+        event.record(&mut fields);
+        let parent = ctx.event_span(event).map(|span| span.id());
+        let mut inner = try_lock!(self.handle.inner.lock(), else return);
+        inner.events.push(CapturedEvent {
+            target: event.metadata().target(),
+            level: *event.metadata().level(),
+            fields: fields.0,
+            parent,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+
+    #[test]
+    fn captures_events_with_fields() {
+        let (layer, handle) = CaptureLayer::new();
+        let subscriber = crate::registry().with(layer);
+
+        tracing_core::dispatch::with_default(&tracing_core::Dispatch::new(subscriber), || {
+            tracing::warn!(status = 404, "not found");
+        });
+
+        handle.assert_event(Level::WARN, module_path!(), |fields| {
+            fields.get("status").map(String::as_str) == Some("404")
+        });
+    }
+
+    #[test]
+    fn captures_span_lifecycle_and_parent() {
+        let (layer, handle) = CaptureLayer::new();
+        let subscriber = crate::registry().with(layer);
+
+        tracing_core::dispatch::with_default(&tracing_core::Dispatch::new(subscriber), || {
+            let parent = tracing::info_span!("request", path = "/users");
+            parent.in_scope(|| {
+                let _child = tracing::info_span!("query", sql = "SELECT 1").entered();
+            });
+        });
+
+        let spans = handle.spans();
+        assert_eq!(spans.len(), 2);
+
+        let parent = spans.iter().find(|s| s.name == "request").unwrap();
+        assert!(parent.is_closed);
+        assert_eq!(parent.parent, None);
+
+        let child = spans.iter().find(|s| s.name == "query").unwrap();
+        assert!(child.is_closed);
+        assert_eq!(child.parent, Some(parent.id.clone()));
+    }
+}
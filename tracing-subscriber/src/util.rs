@@ -35,7 +35,10 @@ where
         #[cfg(feature = "tracing-log")]
         let _ = tracing_log::LogTracer::init();
 
-        dispatch::set_default(&self.into())
+        let guard = dispatch::set_default(&self.into());
+        #[cfg(feature = "startup-banner")]
+        emit_startup_banner();
+        guard
     }
 
     /// Attempts to set `self` as the [global default subscriber] in the current
@@ -66,6 +69,9 @@ where
             .init()
             .map_err(TryInitError::new)?;
 
+        #[cfg(feature = "startup-banner")]
+        emit_startup_banner();
+
         Ok(())
     }
 
@@ -90,6 +96,84 @@ where
 
 impl<T> SubscriberInitExt for T where T: Into<Dispatch> {}
 
+/// Emits a single structured event describing the just-installed subscriber,
+/// so that every log stream begins with machine-readable provenance about
+/// the process that produced it.
+///
+/// This is called automatically by [`SubscriberInitExt::set_default`],
+/// [`SubscriberInitExt::try_init`], and [`SubscriberInitExt::init`] when the
+/// "startup-banner" feature is enabled, after the subscriber they're
+/// installing has already become the current default (so the event is
+/// itself captured by it).
+///
+/// The event only reports information that can be determined generically
+/// from any [`Dispatch`] -- this crate's own version, the process ID, and
+/// the [global max level hint] the new subscriber advertised. It can't
+/// describe the specific layers an application composed into its
+/// subscriber, or the filter directive strings they were built from, since
+/// neither is recoverable from the installed [`Dispatch`] alone.
+///
+/// [global max level hint]: tracing_core::LevelFilter::current
+#[cfg(feature = "startup-banner")]
+#[cfg_attr(docsrs, doc(cfg(feature = "startup-banner")))]
+fn emit_startup_banner() {
+    const VERSION: &str = env!("CARGO_PKG_VERSION");
+    tracing::info!(
+        target: "tracing_subscriber::startup",
+        tracing_subscriber_version = VERSION,
+        pid = std::process::id(),
+        max_level = %tracing_core::LevelFilter::current(),
+        "tracing subscriber installed"
+    );
+}
+
+#[cfg(all(test, feature = "startup-banner"))]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+    use tracing_core::{collect::Interest, span, Collect, Event, Metadata};
+
+    struct RecordingCollector {
+        targets: Arc<Mutex<Vec<&'static str>>>,
+    }
+
+    impl Collect for RecordingCollector {
+        fn register_callsite(&self, _: &'static Metadata<'static>) -> Interest {
+            Interest::always()
+        }
+        fn enabled(&self, _: &Metadata<'_>) -> bool {
+            true
+        }
+        fn new_span(&self, _: &span::Attributes<'_>) -> span::Id {
+            span::Id::from_u64(1)
+        }
+        fn record(&self, _: &span::Id, _: &span::Record<'_>) {}
+        fn record_follows_from(&self, _: &span::Id, _: &span::Id) {}
+        fn event(&self, event: &Event<'_>) {
+            self.targets.lock().unwrap().push(event.metadata().target());
+        }
+        fn enter(&self, _: &span::Id) {}
+        fn exit(&self, _: &span::Id) {}
+        fn current_span(&self) -> span::Current {
+            span::Current::unknown()
+        }
+    }
+
+    #[test]
+    fn set_default_emits_startup_banner() {
+        let targets = Arc::new(Mutex::new(Vec::new()));
+        let _guard = RecordingCollector {
+            targets: targets.clone(),
+        }
+        .set_default();
+
+        assert_eq!(
+            targets.lock().unwrap().as_slice(),
+            &["tracing_subscriber::startup"]
+        );
+    }
+}
+
 /// Error returned by [`try_init`](SubscriberInitExt::try_init) if a global default subscriber could not be initialized.
 pub struct TryInitError {
     inner: Box<dyn Error + Send + Sync + 'static>,
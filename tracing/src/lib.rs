@@ -970,6 +970,9 @@ pub mod field;
 pub mod instrument;
 pub mod level_filters;
 pub mod span;
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub mod sync;
 
 #[doc(hidden)]
 pub mod __macro_support {
@@ -2364,3 +2364,61 @@ macro_rules! if_log_enabled {
         }
     };
 }
+
+/// Constructs a [`field::StructuredValue::Map`][crate::field::StructuredValue::Map]
+/// from a list of `key => value` pairs.
+///
+/// This is a `valuable`-free way to attach map-shaped data to a span or
+/// event field. Values may be primitives, strings, or nested `map!`/`list!`
+/// calls.
+///
+/// This macro requires the `std` feature; `StructuredValue` itself is
+/// available with just `alloc`, but `map!` builds on `std::vec::Vec` and
+/// `std::string::String` directly for simplicity.
+///
+/// # Examples
+///
+/// ```rust
+/// use tracing::{info, map};
+///
+/// info!(request = ?map!{"method" => "GET", "status" => 200_i64});
+/// ```
+#[cfg(feature = "std")]
+#[macro_export]
+macro_rules! map {
+    ($($key:expr => $value:expr),* $(,)?) => {
+        $crate::field::StructuredValue::Map(
+            ::std::vec::Vec::from([
+                $(
+                    (::std::string::String::from($key), $crate::field::StructuredValue::from($value)),
+                )*
+            ])
+        )
+    };
+}
+
+/// Constructs a [`field::StructuredValue::List`][crate::field::StructuredValue::List]
+/// from a list of values.
+///
+/// This is a `valuable`-free way to attach list-shaped data to a span or
+/// event field. Values may be primitives, strings, or nested `map!`/`list!`
+/// calls.
+///
+/// # Examples
+///
+/// ```rust
+/// use tracing::{info, list};
+///
+/// info!(scores = ?list![1_i64, 2_i64, 3_i64]);
+/// ```
+#[cfg(feature = "std")]
+#[macro_export]
+macro_rules! list {
+    ($($value:expr),* $(,)?) => {
+        $crate::field::StructuredValue::List(
+            ::std::vec::Vec::from([
+                $( $crate::field::StructuredValue::from($value), )*
+            ])
+        )
+    };
+}
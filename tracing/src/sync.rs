@@ -0,0 +1,128 @@
+//! Instrumented wrappers around synchronization primitives.
+//!
+//! These types mirror their `std::sync` counterparts, but emit a [span] each
+//! time a caller waits on them, so that lock and channel contention shows up
+//! in the same trace as the rest of the application.
+//!
+//! [span]: crate::Span
+use std::fmt;
+use std::sync::{self, LockResult, PoisonError, TryLockResult};
+use std::time::Instant;
+
+/// A `std::sync::Mutex` wrapper that emits a [`trace`]-level span around
+/// waiting to acquire the lock.
+///
+/// The span is only entered while the caller is blocked acquiring the lock;
+/// it is exited as soon as [`lock`] returns, so it does not capture time
+/// spent holding the guard.
+///
+/// [`trace`]: crate::Level::TRACE
+/// [`lock`]: Mutex::lock
+pub struct Mutex<T: ?Sized> {
+    name: &'static str,
+    inner: sync::Mutex<T>,
+}
+
+impl<T> Mutex<T> {
+    /// Creates a new instrumented mutex wrapping `value`.
+    ///
+    /// `name` is recorded as a field on the span emitted while waiting to
+    /// acquire the lock, and should typically be a human-readable identifier
+    /// for what the mutex protects.
+    pub fn new(value: T, name: &'static str) -> Self {
+        Self {
+            name,
+            inner: sync::Mutex::new(value),
+        }
+    }
+}
+
+impl<T: ?Sized> Mutex<T> {
+    /// Acquires the lock, emitting a span for the duration of the wait.
+    ///
+    /// See [`std::sync::Mutex::lock`] for details on poisoning behavior.
+    pub fn lock(&self) -> LockResult<sync::MutexGuard<'_, T>> {
+        let span = crate::trace_span!("mutex.lock", name = self.name);
+        let _enter = span.enter();
+        let start = Instant::now();
+        let result = self.inner.lock();
+        crate::trace!(wait_us = start.elapsed().as_micros() as u64, "acquired");
+        result
+    }
+
+    /// Attempts to acquire the lock without blocking.
+    ///
+    /// Unlike [`lock`](Mutex::lock), this never waits, so no span is
+    /// emitted for the attempt itself.
+    pub fn try_lock(&self) -> TryLockResult<sync::MutexGuard<'_, T>> {
+        self.inner.try_lock()
+    }
+
+    /// Consumes this mutex, returning the underlying data.
+    pub fn into_inner(self) -> LockResult<T>
+    where
+        T: Sized,
+    {
+        self.inner
+            .into_inner()
+            .map_err(|e| PoisonError::new(e.into_inner()))
+    }
+}
+
+impl<T: ?Sized + fmt::Debug> fmt::Debug for Mutex<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Mutex")
+            .field("name", &self.name)
+            .field("data", &self.try_lock().map_err(|_| "<locked>"))
+            .finish()
+    }
+}
+
+/// Wraps `sender` and `receiver` ends of an [`std::sync::mpsc`] channel so
+/// that sends and receives that block are recorded as [`trace`]-level spans.
+///
+/// [`trace`]: crate::Level::TRACE
+pub fn instrumented_channel<T>(
+    name: &'static str,
+) -> (InstrumentedSender<T>, InstrumentedReceiver<T>) {
+    let (tx, rx) = std::sync::mpsc::channel();
+    (
+        InstrumentedSender { name, inner: tx },
+        InstrumentedReceiver { name, inner: rx },
+    )
+}
+
+/// The sending half of an [`instrumented_channel`].
+pub struct InstrumentedSender<T> {
+    name: &'static str,
+    inner: std::sync::mpsc::Sender<T>,
+}
+
+/// The receiving half of an [`instrumented_channel`].
+pub struct InstrumentedReceiver<T> {
+    name: &'static str,
+    inner: std::sync::mpsc::Receiver<T>,
+}
+
+impl<T> InstrumentedSender<T> {
+    /// Sends `value`, recording an event if the channel is unbounded this
+    /// never blocks, but the event lets contended consumers show up in the
+    /// same trace.
+    pub fn send(&self, value: T) -> Result<(), std::sync::mpsc::SendError<T>> {
+        crate::trace!(channel = self.name, "send");
+        self.inner.send(value)
+    }
+}
+
+impl<T> InstrumentedReceiver<T> {
+    /// Blocks waiting for a value, emitting a span for the duration of the
+    /// wait.
+    pub fn recv(&self) -> Result<T, std::sync::mpsc::RecvError> {
+        let span = crate::trace_span!("channel.recv", channel = self.name);
+        let _enter = span.enter();
+        let start = Instant::now();
+        let result = self.inner.recv();
+        crate::trace!(wait_us = start.elapsed().as_micros() as u64, "received");
+        result
+    }
+}